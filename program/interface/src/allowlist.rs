@@ -0,0 +1,139 @@
+//! Merkle allowlist
+//!
+//! Leaves commit to a wallet and an optional per-wallet amount. Sibling pairs
+//! are sorted before hashing so proofs carry no left/right flags.
+use solana_program::{keccak::hashv, pubkey::Pubkey};
+
+use crate::error::CashError;
+
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+/// Hash an allowlist entry
+pub fn leaf(wallet: &Pubkey, amount: Option<u64>) -> [u8; 32] {
+    match amount {
+        Some(amount) => hashv(&[LEAF_PREFIX, wallet.as_ref(), &amount.to_le_bytes()]).0,
+        None => hashv(&[LEAF_PREFIX, wallet.as_ref()]).0,
+    }
+}
+
+fn node(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    if a <= b {
+        hashv(&[NODE_PREFIX, a, b]).0
+    } else {
+        hashv(&[NODE_PREFIX, b, a]).0
+    }
+}
+
+/// Verify `proof` links `leaf` to `root`
+pub fn verify(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |hash, sibling| node(&hash, sibling));
+    computed == *root
+}
+
+/// Merkle tree built off-chain from an allowlist
+#[cfg(not(target_os = "solana"))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MerkleTree {
+    /// Allowlist entries in leaf order
+    pub entries: Vec<(Pubkey, Option<u64>)>,
+    /// Tree levels from the leaves up to the root
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+#[cfg(not(target_os = "solana"))]
+impl MerkleTree {
+    /// Build a tree from `(wallet, amount)` entries
+    pub fn new(entries: Vec<(Pubkey, Option<u64>)>) -> Result<Self, CashError> {
+        if entries.is_empty() {
+            return Err(CashError::InvalidAllowlistEntry);
+        }
+        let mut levels = vec![entries
+            .iter()
+            .map(|(wallet, amount)| leaf(wallet, *amount))
+            .collect::<Vec<_>>()];
+        while levels[levels.len() - 1].len() > 1 {
+            let level = levels[levels.len() - 1]
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => node(a, b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(level);
+        }
+        Ok(MerkleTree { entries, levels })
+    }
+
+    /// Build a tree from CSV lines of `wallet` or `wallet,amount`
+    pub fn from_csv(csv: &str) -> Result<Self, CashError> {
+        let mut entries = vec![];
+        for line in csv.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let mut columns = line.split(',').map(str::trim);
+            let wallet = match columns.next() {
+                Some(wallet) => wallet
+                    .parse::<Pubkey>()
+                    .map_err(|_| CashError::InvalidAllowlistEntry)?,
+                None => return Err(CashError::InvalidAllowlistEntry),
+            };
+            let amount = match columns.next() {
+                Some(amount) => Some(
+                    amount
+                        .parse::<u64>()
+                        .map_err(|_| CashError::InvalidAllowlistEntry)?,
+                ),
+                None => None,
+            };
+            if columns.next().is_some() {
+                return Err(CashError::InvalidAllowlistEntry);
+            }
+            entries.push((wallet, amount));
+        }
+        Self::new(entries)
+    }
+
+    /// Root stored on the cash link
+    pub fn root(&self) -> [u8; 32] {
+        self.levels[self.levels.len() - 1][0]
+    }
+
+    /// Proof for `wallet`, along with the amount committed in its leaf
+    pub fn proof(&self, wallet: &Pubkey) -> Option<(Vec<[u8; 32]>, Option<u64>)> {
+        let mut index = self.entries.iter().position(|(key, _)| key == wallet)?;
+        let amount = self.entries[index].1;
+        let mut proof = vec![];
+        for level in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = level.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+        Some((proof, amount))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proofs_verify_against_root() {
+        let wallets: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+        let csv = wallets
+            .iter()
+            .enumerate()
+            .map(|(i, wallet)| format!("{},{}", wallet, i * 100))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let tree = MerkleTree::from_csv(&csv).unwrap();
+        let root = tree.root();
+        for (i, wallet) in wallets.iter().enumerate() {
+            let (proof, amount) = tree.proof(wallet).unwrap();
+            assert_eq!(amount, Some(i as u64 * 100));
+            assert!(verify(&proof, &root, leaf(wallet, amount)));
+            assert!(!verify(&proof, &root, leaf(wallet, Some(1))));
+        }
+        assert!(tree.proof(&Pubkey::new_unique()).is_none());
+    }
+}
@@ -0,0 +1,118 @@
+//! Helpers for programs creating cash links through CPI
+//!
+//! The owner of a link created by another program is usually one of its PDAs.
+//! A PDA holding data cannot be debited by the system program, so
+//! `init_cash_link` moves the owner's lamport deposit to the cash link
+//! address itself before invoking the cash program, which then checks the
+//! deposit is there instead of transferring it.
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program::invoke_signed,
+    program_error::ProgramError, system_program,
+};
+
+use crate::{
+    error::CashError,
+    instruction::{self, InitCashLinkArgs},
+    quote::deposit_quote,
+};
+
+/// Token accounts of a cash link holding an SPL token
+pub struct TokenAccounts<'a, 'b> {
+    pub mint: &'b AccountInfo<'a>,
+    /// The cash link's associated token account
+    pub vault: &'b AccountInfo<'a>,
+    /// The owner's associated token account
+    pub owner_token: &'b AccountInfo<'a>,
+    pub associated_token_program: &'b AccountInfo<'a>,
+    pub token_program: &'b AccountInfo<'a>,
+}
+
+/// Accounts of an `InitCashLink` CPI
+pub struct InitCashLinkAccounts<'a, 'b> {
+    pub cash_program: &'b AccountInfo<'a>,
+    pub authority: &'b AccountInfo<'a>,
+    /// Signer of the deposit, a PDA of the calling program signed for with
+    /// the signer seeds
+    pub owner: &'b AccountInfo<'a>,
+    pub fee_payer: &'b AccountInfo<'a>,
+    pub cash_link: &'b AccountInfo<'a>,
+    pub pass_key: &'b AccountInfo<'a>,
+    pub rent: &'b AccountInfo<'a>,
+    pub system_program: &'b AccountInfo<'a>,
+    pub clock: &'b AccountInfo<'a>,
+    /// The program config
+    pub config: &'b AccountInfo<'a>,
+    /// Required if the link holds an SPL token
+    pub token: Option<TokenAccounts<'a, 'b>>,
+}
+
+/// Create a cash link through CPI. Bundles and stake links are not supported
+pub fn init_cash_link(
+    accounts: &InitCashLinkAccounts,
+    args: InitCashLinkArgs,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    if args.bundle.is_some() {
+        return Err(CashError::InvalidBundle.into());
+    }
+    if args.stake.is_some() {
+        return Err(CashError::InvalidStake.into());
+    }
+    let mint = accounts.token.as_ref().map(|token| token.mint.key);
+    if accounts.owner.owner != &system_program::id() {
+        let (_, lamport_deposit) = deposit_quote(&args)?
+            .split(mint.is_none(), args.fees_in_sol.unwrap_or_default())?;
+        prefund(accounts.owner, accounts.cash_link, lamport_deposit)?;
+    }
+    let instruction = instruction::init_cash_link(
+        accounts.cash_program.key,
+        accounts.authority.key,
+        accounts.owner.key,
+        accounts.fee_payer.key,
+        accounts.cash_link.key,
+        accounts.pass_key.key,
+        mint,
+        args,
+    );
+    let mut account_infos = vec![
+        accounts.authority.clone(),
+        accounts.owner.clone(),
+        accounts.fee_payer.clone(),
+        accounts.cash_link.clone(),
+        accounts.pass_key.clone(),
+        accounts.rent.clone(),
+        accounts.system_program.clone(),
+        accounts.clock.clone(),
+    ];
+    if let Some(token) = &accounts.token {
+        account_infos.extend([
+            token.mint.clone(),
+            token.vault.clone(),
+            token.owner_token.clone(),
+            token.associated_token_program.clone(),
+            token.token_program.clone(),
+        ]);
+    }
+    account_infos.push(accounts.config.clone());
+    account_infos.push(accounts.cash_program.clone());
+    invoke_signed(&instruction, &account_infos, signers_seeds)
+}
+
+/// Move `amount` lamports from an account owned by the calling program to the
+/// address of a cash link about to be created
+fn prefund(owner: &AccountInfo, cash_link: &AccountInfo, amount: u64) -> ProgramResult {
+    if amount == 0 {
+        return Ok(());
+    }
+    let owner_lamports = owner
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    let cash_link_lamports = cash_link
+        .lamports()
+        .checked_add(amount)
+        .ok_or(CashError::Overflow)?;
+    **owner.lamports.borrow_mut() = owner_lamports;
+    **cash_link.lamports.borrow_mut() = cash_link_lamports;
+    Ok(())
+}
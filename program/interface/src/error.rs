@@ -1,8 +1,10 @@
 // inside error.rs
+use borsh::BorshDeserialize;
 use thiserror::Error;
 use solana_program::program_error::ProgramError;
 
-#[derive(Error, Debug, Copy, Clone)]
+/// Errors of the program, their code being their index
+#[derive(Error, BorshDeserialize, Debug, Copy, Clone, PartialEq, Eq)]
 pub enum CashError {
     /// Invalid instruction
     #[error("Invalid Owner")]
@@ -21,15 +23,15 @@ pub enum CashError {
     AmountOverflow,
     #[error("Account already settled")]
     AccountAlreadyRedeemed,
-    #[error("Account already canceled")]
+    #[error("Account already expired")]
     AccountAlreadyExpired,
     #[error("Fee overflow")]
     FeeOverflow,
     #[error("Account not redeemed or initialized")]
     AccountNotRedeemedOrInitialized,
-    #[error("Account not redeemed or canceled")]
+    #[error("Account not redeemed or cancelled")]
     AccountNotRedeemedOrCanceled,
-    #[error("Account not canceled")]
+    #[error("Account not expired or cancelled")]
     AccountNotExpired,
     #[error("Account not initialized")]
     AccountNotInitialized,
@@ -99,6 +101,70 @@ pub enum CashError {
     InvalidCashLinkReference,
     #[error("Pass Key is invalid")]
     InvalidPassKey,
+    #[error("Allowlist proof was not found")]
+    AllowlistProofNotFound,
+    #[error("Wallet is not on the allowlist")]
+    WalletNotAllowlisted,
+    #[error("Invalid allowlist entry")]
+    InvalidAllowlistEntry,
+    #[error("Claim signature was not found")]
+    ClaimSignatureNotFound,
+    #[error("Claim authorization has expired")]
+    ClaimExpired,
+    #[error("Instructions sysvar was not found")]
+    InstructionsSysvarNotFound,
+    #[error("Hashlock secret was not found")]
+    SecretNotFound,
+    #[error("Hashlock secret is invalid")]
+    InvalidSecret,
+    #[error("Max claims per wallet must be greater than 0")]
+    InvalidMaxClaimsPerWallet,
+    #[error("Wallet has reached the max claims for this cash link")]
+    MaxClaimsPerWalletReached,
+    #[error("Redemption does not belong to this cash link and wallet")]
+    InvalidRedemption,
+    #[error("Cash link is cooling down since its last redemption")]
+    CooldownActive,
+    #[error("Wallet is cooling down since its last claim")]
+    WalletCooldownActive,
+    #[error("Invalid bundle")]
+    InvalidBundle,
+    #[error("Mint is not a valid NFT for an NFT cash link")]
+    InvalidNft,
+    #[error("Fees paid in SOL cannot include a bps fee on the token amount")]
+    InvalidSolFee,
+    #[error("Recipient account not found")]
+    RecipientNotFound,
+    #[error("Invalid batch of cash links")]
+    InvalidBatch,
+    #[error("Program is paused")]
+    ProgramPaused,
+    #[error("Invalid program config")]
+    InvalidConfig,
+    #[error("Account is frozen")]
+    AccountFrozen,
+    #[error("Account not frozen")]
+    AccountNotFrozen,
+    #[error("Account already cancelled")]
+    AccountAlreadyCancelled,
+    #[error("Cash link was not prefunded with the owner's lamport deposit")]
+    DepositNotPrefunded,
+    #[error("Too many denied programs")]
+    InvalidDeniedPrograms,
+    #[error("Cash link must not be redeemed through CPI")]
+    RedemptionViaCpi,
+    #[error("Transaction calls a program denied by the cash link")]
+    DeniedProgramInTransaction,
+    #[error("Invalid stake account for a stake cash link")]
+    InvalidStake,
+    #[error("Cash links gating their claims cannot be distributed")]
+    GatedDistribution,
+    #[error("Refund wallet has no token account for the mint")]
+    InvalidRefundToken,
+    #[error("Cash link already has the current layout")]
+    CashLinkAlreadyMigrated,
+    #[error("Cash link has the legacy layout and must be migrated first")]
+    CashLinkNotMigrated,
 }
 
 impl From<CashError> for ProgramError {
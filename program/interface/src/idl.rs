@@ -0,0 +1,567 @@
+//! IDL generation
+//!
+//! Describes the program as JSON from its Rust definitions: instruction
+//! variants and their args come from the `CashInstruction` borsh schema,
+//! account layouts from theirs and error codes from `CashError`. The accounts
+//! each instruction expects are kept below in order, as the builders of
+//! `instruction` pass them. The committed `idl/cash.json` is checked by the
+//! tests and regenerated with `UPDATE_IDL=1 cargo test -p cash-interface`.
+use std::collections::{BTreeMap, HashMap};
+
+use borsh::{
+    schema::{Declaration, Definition, Fields},
+    BorshDeserialize, BorshSchema,
+};
+use serde_json::{json, Value};
+use solana_program::program_pack::Pack;
+
+use crate::{
+    error::CashError,
+    instruction::CashInstruction,
+    state::{cashlink::CashLink, config::Config, redemption::Redemption, AccountType},
+};
+
+/// An account an instruction expects. An optional account left out shifts
+/// the accounts after it, so accounts have no fixed position
+#[derive(Clone, Copy)]
+struct AccountDoc {
+    name: &'static str,
+    signer: bool,
+    writable: bool,
+    optional: bool,
+    /// Passed once per link, wallet or bundle asset
+    repeated: bool,
+    docs: &'static str,
+}
+
+impl AccountDoc {
+    const fn new(name: &'static str, docs: &'static str) -> Self {
+        AccountDoc {
+            name,
+            signer: false,
+            writable: false,
+            optional: false,
+            repeated: false,
+            docs,
+        }
+    }
+
+    const fn signer(self) -> Self {
+        AccountDoc { signer: true, ..self }
+    }
+
+    const fn writable(self) -> Self {
+        AccountDoc { writable: true, ..self }
+    }
+
+    const fn optional(self) -> Self {
+        AccountDoc { optional: true, ..self }
+    }
+
+    const fn repeated(self) -> Self {
+        AccountDoc { repeated: true, ..self }
+    }
+
+    fn json(&self) -> Value {
+        json!({
+            "name": self.name,
+            "signer": self.signer,
+            "writable": self.writable,
+            "optional": self.optional,
+            "repeated": self.repeated,
+            "docs": self.docs,
+        })
+    }
+}
+
+const AUTHORITY: AccountDoc = AccountDoc::new("authority", "The cash link authority").signer();
+const CASH_LINK: AccountDoc = AccountDoc::new("cashLink", "The cash link account").writable();
+const PASS_KEY: AccountDoc = AccountDoc::new("passKey", "The pass key the cash link is derived from");
+const RENT: AccountDoc = AccountDoc::new("rent", "The rent sysvar");
+const CLOCK: AccountDoc = AccountDoc::new("clock", "The clock sysvar");
+const SLOT_HASHES: AccountDoc = AccountDoc::new("slotHashes", "The slot hashes sysvar");
+const SYSTEM_PROGRAM: AccountDoc = AccountDoc::new("systemProgram", "The system program");
+const TOKEN_PROGRAM: AccountDoc = AccountDoc::new("tokenProgram", "The token program");
+const ASSOCIATED_TOKEN_PROGRAM: AccountDoc =
+    AccountDoc::new("associatedTokenProgram", "The associated token program");
+const VAULT: AccountDoc =
+    AccountDoc::new("vault", "The vault token account, required if the mint is set")
+        .writable()
+        .optional();
+const STAKE: AccountDoc = AccountDoc::new("stake", "The stake account of a stake link")
+    .writable()
+    .optional();
+const STAKE_PROGRAM: AccountDoc =
+    AccountDoc::new("stakeProgram", "The stake program, required for stake links").optional();
+const BUNDLE_MINT: AccountDoc =
+    AccountDoc::new("bundleMint", "The mint of a bundle asset, the native mint for lamports")
+        .repeated();
+const BUNDLE_VAULT: AccountDoc =
+    AccountDoc::new("bundleVault", "The vault of a bundle asset, the cash link for lamports")
+        .writable()
+        .repeated();
+const CONFIG: AccountDoc =
+    AccountDoc::new("config", "The program config, last. It may not be set yet");
+
+const REFUND_TOKEN: AccountDoc = AccountDoc::new(
+    "refundToken",
+    "The associated token account of `refund_to` for the mint, required if both are set",
+)
+.optional();
+
+const INIT_CASH_LINK: &[AccountDoc] = &[
+    AUTHORITY,
+    AccountDoc::new("owner", "The wallet funding the cash link, writable when it deposits lamports")
+        .signer()
+        .writable(),
+    AccountDoc::new("feePayer", "The fee payer, paying the rent of the new accounts")
+        .signer()
+        .writable(),
+    CASH_LINK,
+    AccountDoc::new("passKey", "The pass key required to unlock the cash link for redemption"),
+    RENT,
+    SYSTEM_PROGRAM,
+    CLOCK,
+    AccountDoc::new("mint", "The token mint of a token link").optional(),
+    VAULT,
+    AccountDoc::new("ownerToken", "The owner token account, required if the mint is set")
+        .writable()
+        .optional(),
+    REFUND_TOKEN,
+    AccountDoc::new(
+        "associatedTokenProgram",
+        "The associated token program, required if the mint is set",
+    )
+    .optional(),
+    AccountDoc::new("tokenProgram", "The token program, required if the mint is set").optional(),
+    AccountDoc { docs: "The stake account of a stake link, whose authorities are the owner", ..STAKE },
+    STAKE_PROGRAM,
+    BUNDLE_MINT,
+    BUNDLE_VAULT,
+    AccountDoc::new("bundleOwnerToken", "The owner token account of a bundle asset, the owner for lamports")
+        .writable()
+        .repeated(),
+    CONFIG,
+];
+
+const REDEEM: &[AccountDoc] = &[
+    AUTHORITY,
+    AccountDoc::new("wallet", "The wallet redeeming the cash link").signer(),
+    AccountDoc::new("feeToken", "The fee token account, the fee wallet when fees are paid in SOL")
+        .writable(),
+    CASH_LINK,
+    AccountDoc::new(
+        "passKey",
+        "The pass key, not a signer for signed claims or hashlocked links",
+    )
+    .signer(),
+    AccountDoc::new("redemption", "The redemption account counting the wallet's claims").writable(),
+    AccountDoc::new("refundToken", "The refund token account, the refund wallet for native links")
+        .writable(),
+    AccountDoc::new("feePayer", "The fee payer, paid the relayer fee").signer().writable(),
+    CLOCK,
+    RENT,
+    SLOT_HASHES,
+    AccountDoc::new("recipientToken", "The recipient token account, required if the mint is set")
+        .writable()
+        .optional(),
+    VAULT,
+    AccountDoc::new("mint", "The token mint, required if the mint is set").optional(),
+    SYSTEM_PROGRAM,
+    AccountDoc::new("fingerprint", "The fingerprint account of fingerprinted links")
+        .writable()
+        .optional(),
+    TOKEN_PROGRAM,
+    AccountDoc::new(
+        "instructions",
+        "The instructions sysvar, required for signed claims and links denying programs",
+    )
+    .optional(),
+    AccountDoc::new(
+        "refundWallet",
+        "The refund wallet, refunded the unused fee deposit of token links and what the last redemption leaves of a bundle",
+    )
+    .writable()
+    .optional(),
+    AccountDoc::new("refundBundleToken", "The refund wallet token account of each bundle mint")
+        .writable()
+        .optional()
+        .repeated(),
+    AccountDoc::new("recipient", "The recipient, when funds go to another account than the wallet")
+        .writable()
+        .optional(),
+    STAKE,
+    STAKE_PROGRAM,
+    BUNDLE_MINT,
+    BUNDLE_VAULT,
+    AccountDoc::new(
+        "bundleRecipientToken",
+        "The recipient token account of a bundle asset, the recipient for lamports",
+    )
+    .writable()
+    .repeated(),
+    CONFIG,
+];
+
+const CANCEL: &[AccountDoc] = &[
+    AUTHORITY,
+    CASH_LINK,
+    PASS_KEY,
+    AccountDoc::new("refundToken", "The refund token account, the refund wallet for native links")
+        .writable(),
+    AccountDoc::new("feePayer", "The fee payer, receiving the rent of the closed vault").writable(),
+    CLOCK,
+    RENT,
+    VAULT,
+    TOKEN_PROGRAM,
+    SYSTEM_PROGRAM,
+    AccountDoc::new("refundWallet", "The refund wallet, refunded the fee deposit of token links")
+        .writable(),
+    STAKE,
+    STAKE_PROGRAM,
+    BUNDLE_MINT,
+    BUNDLE_VAULT,
+    AccountDoc::new(
+        "bundleRefundToken",
+        "The refund token account of a bundle asset, the refund wallet for lamports",
+    )
+    .writable()
+    .repeated(),
+];
+
+const CLOSE: &[AccountDoc] = &[
+    AUTHORITY,
+    CASH_LINK,
+    AccountDoc::new("feePayer", "The fee payer, receiving the rent of the cash link").writable(),
+    SYSTEM_PROGRAM,
+];
+
+const BATCH_INIT_CASH_LINKS: &[AccountDoc] = &[
+    AUTHORITY,
+    AccountDoc::new("owner", "The wallet funding the cash links").signer().writable(),
+    AccountDoc::new("feePayer", "The fee payer, paying the rent of the new accounts")
+        .signer()
+        .writable(),
+    RENT,
+    SYSTEM_PROGRAM,
+    CLOCK,
+    TOKEN_PROGRAM,
+    ASSOCIATED_TOKEN_PROGRAM,
+    AccountDoc::new("mint", "The token mint of token links").optional(),
+    AccountDoc::new("ownerToken", "The owner token account, required if the mint is set")
+        .writable()
+        .optional(),
+    REFUND_TOKEN,
+    AccountDoc::new("cashLink", "The cash link account of each link").writable().repeated(),
+    AccountDoc::new("passKey", "The pass key of each link").repeated(),
+    AccountDoc::new("vault", "The vault of each link, required if the mint is set")
+        .writable()
+        .optional()
+        .repeated(),
+    CONFIG,
+];
+
+const DISTRIBUTE: &[AccountDoc] = &[
+    AUTHORITY,
+    CASH_LINK,
+    PASS_KEY,
+    AccountDoc::new("feeToken", "The fee token account, the fee wallet when fees are paid in SOL")
+        .writable(),
+    AccountDoc::new("refundToken", "The refund token account, the refund wallet for native links")
+        .writable(),
+    AccountDoc::new("feePayer", "The fee payer, paid the relayer fees").signer().writable(),
+    CLOCK,
+    RENT,
+    SLOT_HASHES,
+    SYSTEM_PROGRAM,
+    TOKEN_PROGRAM,
+    ASSOCIATED_TOKEN_PROGRAM,
+    VAULT,
+    AccountDoc::new("mint", "The token mint, required if the mint is set").optional(),
+    AccountDoc::new("wallet", "Each wallet paid out").writable().repeated(),
+    AccountDoc::new("redemption", "The redemption account of each wallet").writable().repeated(),
+    AccountDoc::new("walletToken", "The token account of each wallet, required if the mint is set")
+        .writable()
+        .optional()
+        .repeated(),
+    AccountDoc::new("refundWallet", "The refund wallet, refunded the unused fee deposit of token links")
+        .writable()
+        .optional(),
+    CONFIG,
+];
+
+const REFUND: &[AccountDoc] = &[
+    AccountDoc::new("cranker", "The cranker, paid the refund bounty").signer().writable(),
+    CASH_LINK,
+    PASS_KEY,
+    AccountDoc::new(
+        "refundWallet",
+        "The refund wallet, `refund_to` if set, else the owner, receiving the rent of the closed vaults",
+    )
+    .writable(),
+    AccountDoc::new("refundToken", "The refund token account, the refund wallet for native links")
+        .writable(),
+    CLOCK,
+    RENT,
+    VAULT,
+    TOKEN_PROGRAM,
+    SYSTEM_PROGRAM,
+    STAKE,
+    STAKE_PROGRAM,
+    BUNDLE_MINT,
+    BUNDLE_VAULT,
+    AccountDoc::new(
+        "bundleRefundToken",
+        "The refund token account of a bundle asset, the refund wallet for lamports",
+    )
+    .writable()
+    .repeated(),
+];
+
+const SET_CONFIG: &[AccountDoc] = &[
+    AccountDoc::new("signer", "The upgrade authority on creation, the config admin afterwards")
+        .signer(),
+    AccountDoc::new("config", "The program config").writable(),
+    AccountDoc::new("feePayer", "The fee payer").signer().writable(),
+    RENT,
+    SYSTEM_PROGRAM,
+    AccountDoc::new("programData", "The program data account, required on creation").optional(),
+];
+
+const SET_FROZEN: &[AccountDoc] = &[AUTHORITY, CASH_LINK];
+
+const MIGRATE_CASH_LINK: &[AccountDoc] = &[
+    AccountDoc::new("feePayer", "The fee payer, paying the rent of the added space")
+        .signer()
+        .writable(),
+    CASH_LINK,
+    RENT,
+    SYSTEM_PROGRAM,
+];
+
+/// What an instruction does and the accounts it expects
+fn instruction_docs(name: &str) -> (&'static str, &'static [AccountDoc]) {
+    match name {
+        "InitCashLink" => ("Create and fund a cash link", INIT_CASH_LINK),
+        "Redeem" => ("Redeem the cash link", REDEEM),
+        "Cancel" => ("Cancel the cash link, refunding what is left of it", CANCEL),
+        "Close" => ("Close the account of an expired or cancelled cash link", CLOSE),
+        "BatchInitCashLinks" => (
+            "Initialize many cash links sharing the same arguments, funded by a single deposit from the owner",
+            BATCH_INIT_CASH_LINKS,
+        ),
+        "Distribute" => ("Push payouts of a cash link to known wallets", DISTRIBUTE),
+        "Refund" => ("Refund an expired cash link to its refund address", REFUND),
+        "SetConfig" => ("Create or update the program config", SET_CONFIG),
+        "SetFrozen" => ("Freeze a cash link for review, or release it", SET_FROZEN),
+        "MigrateCashLink" => (
+            "Reallocate a cash link created with the legacy layout to the current one",
+            MIGRATE_CASH_LINK,
+        ),
+        _ => ("", &[]),
+    }
+}
+
+/// Borsh definitions of every type used by instruction args and accounts
+fn definitions() -> BTreeMap<Declaration, Definition> {
+    let mut definitions = HashMap::new();
+    CashInstruction::add_definitions_recursively(&mut definitions);
+    CashLink::add_definitions_recursively(&mut definitions);
+    Redemption::add_definitions_recursively(&mut definitions);
+    Config::add_definitions_recursively(&mut definitions);
+    definitions.into_iter().collect()
+}
+
+/// The `(name, args)` of every `CashInstruction` variant, in order
+fn variants(definitions: &BTreeMap<Declaration, Definition>) -> Vec<(String, Option<Declaration>)> {
+    let variants = match &definitions[&CashInstruction::declaration()] {
+        Definition::Enum { variants } => variants,
+        _ => unreachable!("CashInstruction is an enum"),
+    };
+    variants
+        .iter()
+        .map(|(name, declaration)| {
+            let args = match &definitions[declaration] {
+                Definition::Struct {
+                    fields: Fields::UnnamedFields(fields),
+                } => fields.first().cloned(),
+                _ => None,
+            };
+            (name.clone(), args)
+        })
+        .collect()
+}
+
+fn definition_json(definition: &Definition) -> Value {
+    match definition {
+        Definition::Array { length, elements } => json!({ "array": [elements, length] }),
+        Definition::Sequence { elements } => json!({ "vec": elements }),
+        Definition::Tuple { elements } => json!({ "tuple": elements }),
+        Definition::Enum { variants } => json!({
+            "kind": "enum",
+            "variants": variants
+                .iter()
+                .map(|(name, declaration)| json!({ "name": name, "type": declaration }))
+                .collect::<Vec<_>>(),
+        }),
+        Definition::Struct { fields } => json!({
+            "kind": "struct",
+            "fields": match fields {
+                Fields::NamedFields(fields) => fields
+                    .iter()
+                    .map(|(name, declaration)| json!({ "name": name, "type": declaration }))
+                    .collect::<Vec<_>>(),
+                Fields::UnnamedFields(fields) => fields.iter().map(|field| json!(field)).collect(),
+                Fields::Empty => vec![],
+            },
+        }),
+    }
+}
+
+fn instructions(definitions: &BTreeMap<Declaration, Definition>) -> Vec<Value> {
+    variants(definitions)
+        .into_iter()
+        .enumerate()
+        .map(|(index, (name, args))| {
+            let (docs, accounts) = instruction_docs(&name);
+            json!({
+                "name": name,
+                "index": index,
+                "docs": docs,
+                "args": args,
+                "accounts": accounts.iter().map(AccountDoc::json).collect::<Vec<_>>(),
+            })
+        })
+        .collect()
+}
+
+/// Every `CashError`, its code being its borsh variant index
+fn cash_errors() -> Vec<CashError> {
+    (0..=u8::MAX)
+        .map_while(|code| CashError::try_from_slice(&[code]).ok())
+        .collect()
+}
+
+fn errors() -> Vec<Value> {
+    cash_errors()
+        .into_iter()
+        .map(|error| {
+            json!({
+                "code": error as u32,
+                "name": format!("{:?}", error),
+                "msg": error.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn account(name: &str, account_type: AccountType, size: usize) -> Value {
+    json!({
+        "name": name,
+        "accountType": account_type as u8,
+        "size": size,
+        "type": name,
+    })
+}
+
+/// The program IDL
+pub fn idl() -> Value {
+    let definitions = definitions();
+    let instruction_declarations: Vec<Declaration> = match &definitions[&CashInstruction::declaration()] {
+        Definition::Enum { variants } => variants.iter().map(|(_, declaration)| declaration.clone()).collect(),
+        _ => unreachable!("CashInstruction is an enum"),
+    };
+    json!({
+        "name": "cash",
+        "version": env!("CARGO_PKG_VERSION"),
+        "address": crate::id().to_string(),
+        "instructions": instructions(&definitions),
+        "accounts": [
+            account("CashLink", AccountType::CashLink, CashLink::LEN),
+            account("Redemption", AccountType::Redemption, Redemption::LEN),
+            account("Config", AccountType::Config, Config::LEN),
+        ],
+        // the instruction enum and its variants are described by `instructions`
+        "types": definitions
+            .iter()
+            .filter(|(declaration, _)| {
+                **declaration != CashInstruction::declaration()
+                    && !instruction_declarations.contains(declaration)
+            })
+            .map(|(declaration, definition)| (declaration.clone(), definition_json(definition)))
+            .collect::<serde_json::Map<_, _>>(),
+        "errors": errors(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use borsh::BorshSerialize;
+    use solana_program::program_error::ProgramError;
+    use std::{fs, path::Path};
+
+    use crate::instruction::SetFrozenArgs;
+
+    fn index_of(idl: &Value, name: &str, kind: &str) -> u64 {
+        idl[kind]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|item| item["name"] == name)
+            .unwrap_or_else(|| panic!("{} {} not found", kind, name))[if kind == "errors" { "code" } else { "index" }]
+            .as_u64()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_idl_is_up_to_date() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("idl/cash.json");
+        let idl = serde_json::to_string_pretty(&idl()).unwrap() + "\n";
+        if std::env::var("UPDATE_IDL").is_ok() {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, &idl).unwrap();
+        }
+        assert!(
+            fs::read_to_string(&path).ok().as_deref() == Some(idl.as_str()),
+            "idl/cash.json is stale, regenerate it with UPDATE_IDL=1 cargo test -p cash-interface"
+        );
+    }
+
+    #[test]
+    fn test_instructions_match_cash_instruction() {
+        let idl = idl();
+        let close = CashInstruction::Close.try_to_vec().unwrap();
+        assert_eq!(index_of(&idl, "Close", "instructions"), close[0] as u64);
+        let frozen = CashInstruction::SetFrozen(SetFrozenArgs { frozen: true })
+            .try_to_vec()
+            .unwrap();
+        assert_eq!(index_of(&idl, "SetFrozen", "instructions"), frozen[0] as u64);
+        assert_eq!(idl["instructions"][1]["args"], "InitCashRedemptionArgs");
+        assert!(idl["instructions"][3]["args"].is_null());
+        for instruction in idl["instructions"].as_array().unwrap() {
+            let name = &instruction["name"];
+            assert!(!instruction["docs"].as_str().unwrap().is_empty(), "missing docs for {}", name);
+            assert!(!instruction["accounts"].as_array().unwrap().is_empty(), "missing accounts for {}", name);
+            if let Some(args) = instruction["args"].as_str() {
+                assert!(idl["types"].get(args).is_some(), "missing schema for {}", args);
+            }
+        }
+    }
+
+    #[test]
+    fn test_errors_match_cash_error() {
+        let idl = idl();
+        let errors = idl["errors"].as_array().unwrap();
+        let cash_errors = cash_errors();
+        assert_eq!(errors.len(), cash_errors.len());
+        assert!(CashError::try_from_slice(&[cash_errors.len() as u8]).is_err());
+        for (code, error) in cash_errors.into_iter().enumerate() {
+            assert_eq!(error as usize, code);
+            assert_eq!(ProgramError::from(error), ProgramError::Custom(code as u32));
+            assert_eq!(errors[code]["code"], code);
+            assert_eq!(errors[code]["name"], format!("{:?}", error));
+            assert_eq!(errors[code]["msg"], error.to_string());
+        }
+    }
+}
@@ -0,0 +1,769 @@
+//! Instruction types
+#![allow(missing_docs)]
+#![allow(clippy::too_many_arguments)]
+// the `BorshSchema` derive of `CashInstruction` declares a struct per variant
+// whose fields are never read
+#![allow(dead_code)]
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    stake, system_program, sysvar,
+};
+
+use crate::{
+    find_config_program_address,
+    spl::{self, get_associated_token_address},
+    state::cashlink::DistributionType,
+};
+
+/// Initialize a cash_link arguments
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug, Clone, Default)]
+/// Initialize a cash_link params
+pub struct InitCashLinkArgs {
+    pub amount: u64,
+    pub fee_bps: u16,
+    pub fixed_fee: u64,
+    pub fee_to_redeem: u64,
+    pub cash_link_bump: u8,
+    pub distribution_type: DistributionType,
+    pub max_num_redemptions: u16,
+    pub min_amount: Option<u64>,
+    pub fingerprint_enabled: Option<bool>,
+    pub num_days_to_expire: u8,
+    pub allowlist_root: Option<[u8; 32]>,
+    /// sha256 of the secret unlocking the link. The pass key account must be
+    /// the hash so the cash link address is derived from it. The hash is
+    /// public and unsalted, so a short secret is not kept from anyone who
+    /// reads the account: redemptions are gated by the authority signature
+    pub hashlock: Option<[u8; 32]>,
+    /// Claims a single wallet may make, defaults to 1. Wallets allowlisted
+    /// with an amount claim it once regardless
+    pub max_claims_per_wallet: Option<u16>,
+    /// Seconds between any two redemptions of the link
+    pub cooldown_seconds: Option<u64>,
+    /// Seconds between two claims by the same wallet
+    pub wallet_cooldown_seconds: Option<u64>,
+    /// Extra assets paid out alongside `amount`, see `BundleAsset`
+    pub bundle: Option<Vec<BundleAssetArgs>>,
+    /// Gift a single NFT: the mint must have a supply of 1 and 0 decimals,
+    /// `amount` must be 1 and fees are paid in SOL. `max_num_redemptions` is
+    /// ignored and set to 1
+    pub nft: Option<bool>,
+    /// Deposit the fees of a token link in lamports and pay them in SOL, so
+    /// the vault only holds the amount. Requires a zero `fee_bps`
+    pub fees_in_sol: Option<bool>,
+    /// Lamports paid to the signing fee payer of every redemption, deposited
+    /// on the cash link at init
+    pub relayer_fee: Option<u64>,
+    /// Lamports paid to whoever refunds the link once it expires, deposited
+    /// on the cash link at init
+    pub refund_bounty: Option<u64>,
+    /// Reject redemptions invoked through CPI, as bots wrap claims in
+    /// programs reverting unless the random amount is large
+    pub forbid_cpi: Option<bool>,
+    /// Reject redemption transactions calling any of these programs, at
+    /// most `MAX_DENIED_PROGRAMS`
+    pub denied_programs: Option<Vec<Pubkey>>,
+    /// Gift this stake account instead of lamports or tokens. Its staker and
+    /// withdrawer authorities move from the owner to the cash link, then to
+    /// the redeemer. `amount` must be its balance, `max_num_redemptions` 1
+    /// and `fee_bps` 0
+    pub stake: Option<Pubkey>,
+    /// Wallet refunded in place of the owner when the link is cancelled,
+    /// refunded or fully redeemed. Defaults to the owner. A token link is
+    /// passed its token account for the mint on init
+    pub refund_to: Option<Pubkey>,
+}
+
+/// An extra asset to escrow in a bundle cash link
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug, Clone)]
+pub struct BundleAssetArgs {
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+/// Initialize many identical cash links arguments
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug, Clone)]
+pub struct BatchInitCashLinksArgs {
+    /// Arguments shared by every link, their `cash_link_bump` is ignored
+    pub args: InitCashLinkArgs,
+    /// Bump of each cash link, in the order of the link accounts
+    pub cash_link_bumps: Vec<u8>,
+}
+
+/// Initialize a redemption arguments
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug, Clone, Default)]
+/// Initialize a cash_link params
+pub struct InitCashRedemptionArgs {
+    pub redemption_bump: u8,
+    pub cash_link_bump: u8,
+    pub fingerprint: Option<String>,
+    pub fingerprint_bump: Option<u8>,
+    pub allowlist_proof: Option<Vec<[u8; 32]>>,
+    pub allowlist_amount: Option<u64>,
+    /// Set to redeem with a claim signed by the pass key instead of the pass
+    /// key signing the transaction
+    pub claim_expires_at: Option<u64>,
+    /// Preimage of the hashlock, required to redeem hashlocked links
+    pub secret: Option<String>,
+    /// Account receiving the funds in place of the wallet, which still signs
+    /// and is counted against `max_claims_per_wallet`
+    pub recipient: Option<Pubkey>,
+}
+
+/// Distribute a cash link arguments
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug, Clone)]
+pub struct DistributeArgs {
+    pub cash_link_bump: u8,
+    /// Bump of each wallet's redemption account, in the order of the wallets
+    pub redemption_bumps: Vec<u8>,
+}
+
+/// Refund an expired cash link arguments
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug, Clone)]
+pub struct RefundArgs {
+    pub cash_link_bump: u8,
+}
+
+/// Create or update the program config arguments
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug, Clone)]
+pub struct SetConfigArgs {
+    pub config_bump: u8,
+    pub admin: Pubkey,
+    pub paused: bool,
+}
+
+/// Freeze or release a cash link arguments
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug, Clone)]
+pub struct SetFrozenArgs {
+    pub frozen: bool,
+}
+
+/// Cancel a cash link
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Debug, Clone)]
+/// Cancel a cash_link params
+pub struct CancelCashRedemptionArgs {
+    pub cash_link_bump: u8,
+    /// Reason code stored on the cancelled link for reporting
+    pub reason: Option<u8>,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq, Clone,)]
+pub enum CashInstruction {
+
+    /// Starts the trade by creating and populating an cash_link account and transferring ownership of the given temp token account to the PDA
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]`   The cash_link authority responsible for approving / refunding payments due to some external conditions
+    /// 1. `[signer][writable]`The account of the wallet owner initializing the cashlink, writable when it deposits lamports
+    /// 2. `[signer][writable]` The fee payer
+    /// 3. `[writable]` The cash link account, it will hold all necessary info about the trade.
+    /// 4. `[]` The pass key required to unlock the cash link for redemption
+    /// 5. `[]` The rent sysvar
+    /// 6. `[]` The system program
+    /// 7. `[]` The clock account
+    /// 8. `[]` The token mint (Optional)
+    /// 9. `[writable]` The associated token for the mint derived from the cash link account (Optional)
+    /// 10. `[writable]` The owner token that must be passed if pay is true and mint is some Optional)
+    /// 11. `[][Optional]` The associated token account of `refund_to` for the mint, required if both are set
+    /// 12. `[][Optional]` The associated token program, required if the mint is set
+    /// 13. `[][Optional]` The token program, required if the mint is set
+    /// 14. `[writable][Optional]` The stake account of a stake link, whose authorities are the owner
+    /// 15. `[][Optional]` The stake program, required for stake links
+    /// 16. ..  `[writable]` The `(mint, vault, owner token)` accounts of each bundle asset
+    /// 17. `[]` The program config, last. It may not be set yet
+    InitCashLink (InitCashLinkArgs),
+    /// Redeem the cashlink
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the authority
+    /// 1. `[signer][writable]` The user wallet, paid native links unless a recipient is set
+    /// 2. `[writable]` The fee token account for the token they will receive should the trade go through, the fee wallet when fees are paid in SOL
+    /// 3. `[writable]` The cash_link account holding the cash_link info
+    /// 4. `[signer]` The pass key required to unlock the cash link for redemption, not a signer for signed claims or hashlocked links
+    /// 5. `[writable]` The redemption account counting the wallet's claims on this cashlink
+    /// 6. `[writable]` The refund token account, the refund wallet for native links: `refund_to` if set, else the owner
+    /// 7. `[signer][writable]` The fee payer, paid the relayer fee
+    /// 8. `[]` The clock account
+    /// 9. `[]` The rent account
+    /// 10. `[]` The recent slot hash account
+    /// 11. `[writable][Optional]` The recipient token account for the token they will receive should the trade go through, owned by the recipient if set
+    /// 12. `[writable][Optional]` The vault token account to get tokens. This value is Optional. if the mint is set, then this must be set.
+    /// 13. `[][Optional]` The mint account for the token
+    /// 14. `[]` The system program
+    /// 15. `[writable][Optional]` The fingerprint info
+    /// 16. `[]` The token program
+    /// 17. `[][Optional]` The instructions sysvar, required for signed claims and links denying programs
+    /// 18. `[writable][Optional]` The refund wallet, refunded the unused fee deposit of token links and
+    ///     what the last redemption leaves of a bundle, followed by its token account of each bundle mint
+    /// 19. `[writable][Optional]` The recipient, when funds go to another account than the wallet
+    /// 20. `[writable][Optional]` The stake account of a stake link
+    /// 21. `[][Optional]` The stake program, required for stake links
+    /// 22. ..  `[writable]` The `(mint, vault, recipient token)` accounts of each bundle asset
+    /// 23. `[]` The program config, last. It may not be set yet
+    Redeem(InitCashRedemptionArgs),
+    /// Cancel the cash_link
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the authority
+    /// 1. `[writable]` The cash_link account holding the cash_link info   
+    /// 2. `[]` The pass key required to unlock the cash link for redemption
+    /// 3. `[writable]` The refund token account, the refund wallet for native links: `refund_to` if set, else the owner
+    /// 4. `[writable]` The fee payer token account to receive tokens from the vault
+    /// 5. `[]` The clock account
+    /// 6. `[]` The rent account
+    /// 7. `[writable]` The vault token account to get tokens from and eventually close. This value is Optional. if the mint is set, then this must be set.
+    /// 8. `[]` The token program
+    /// 9. `[]` The system program
+    /// 10. `[writable]` The refund wallet, refunded the fee deposit of token links
+    /// 11. `[writable][Optional]` The stake account of a stake link
+    /// 12. `[][Optional]` The stake program, required for stake links
+    /// 13. ..  `[writable]` The `(mint, vault, refund token)` accounts of each bundle asset, last
+    Cancel(CancelCashRedemptionArgs),
+    /// Close the cash_link
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the authority
+    /// 1. `[writable]` The cash_link account holding the cash_link info     
+    /// 2. `[writable]` The fee payer's main account to send their rent fees to
+    /// 3. `[]` The system program
+    Close,
+    /// Initialize many cash links sharing the same arguments, funded by a
+    /// single deposit from the owner that the program splits between them.
+    /// Hashlocks, bundles, NFTs and stake links are not supported
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The cash_link authority
+    /// 1. `[signer][writable]` The account of the wallet owner funding the cash links
+    /// 2. `[signer][writable]` The fee payer
+    /// 3. `[]` The rent sysvar
+    /// 4. `[]` The system program
+    /// 5. `[]` The clock account
+    /// 6. `[]` The token program
+    /// 7. `[]` The associated token program
+    /// 8. `[][Optional]` The token mint
+    /// 9. `[writable][Optional]` The owner token account, required if the mint is set
+    /// 10. `[][Optional]` The associated token account of `refund_to` for the mint, required if both are set
+    /// 11. .. `[writable]` The `(cash link, pass key)` accounts of each link, followed by its vault if the mint is set
+    /// 12. `[]` The program config, last. It may not be set yet
+    BatchInitCashLinks(BatchInitCashLinksArgs),
+    /// Push payouts of a cash link to known wallets, no pass key signature or
+    /// claim required. Each wallet is held to the max claims per wallet. Not
+    /// supported for bundle and stake cash links, nor for links with an
+    /// allowlist or a cooldown
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the authority
+    /// 1. `[writable]` The cash_link account holding the cash_link info
+    /// 2. `[]` The pass key the cash link is derived from
+    /// 3. `[writable]` The fee token account, the fee wallet when fees are paid in SOL
+    /// 4. `[writable]` The refund token account, the refund wallet for native links
+    /// 5. `[signer][writable]` The fee payer, paid the relayer fees
+    /// 6. `[]` The clock account
+    /// 7. `[]` The rent account
+    /// 8. `[]` The recent slot hash account
+    /// 9. `[]` The system program
+    /// 10. `[]` The token program
+    /// 11. `[]` The associated token program
+    /// 12. `[writable][Optional]` The vault token account, required if the mint is set
+    /// 13. `[][Optional]` The mint account, required if the mint is set
+    /// 14. .. `[writable]` The `(wallet, redemption)` accounts of each wallet, followed by its token account if the mint is set,
+    ///     then `[writable][Optional]` the refund wallet, refunded the unused fee deposit of token links
+    /// 15. `[]` The program config, last. It may not be set yet
+    Distribute(DistributeArgs),
+    /// Refund an expired cash link to its refund address. Permissionless, the
+    /// signer is paid the link's refund bounty
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer][writable]` The cranker, paid the refund bounty
+    /// 1. `[writable]` The cash_link account holding the cash_link info
+    /// 2. `[]` The pass key the cash link is derived from
+    /// 3. `[writable]` The refund wallet, `refund_to` if set, else the owner, receiving the rent of the closed vaults
+    /// 4. `[writable]` The refund token account, the refund wallet for native links
+    /// 5. `[]` The clock account
+    /// 6. `[]` The rent account
+    /// 7. `[writable][Optional]` The vault token account, required if the mint is set
+    /// 8. `[]` The token program
+    /// 9. `[]` The system program
+    /// 10. `[writable][Optional]` The stake account of a stake link
+    /// 11. `[][Optional]` The stake program, required for stake links
+    /// 12. ..  `[writable]` The `(mint, vault, refund token)` accounts of each bundle asset, last
+    Refund(RefundArgs),
+    /// Create or update the program config. Creating it requires the program
+    /// upgrade authority, updating it the config admin
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The upgrade authority on creation, the config admin afterwards
+    /// 1. `[writable]` The program config
+    /// 2. `[signer][writable]` The fee payer
+    /// 3. `[]` The rent account
+    /// 4. `[]` The system program
+    /// 5. `[][Optional]` The program data account, required on creation
+    SetConfig(SetConfigArgs),
+    /// Freeze a cash link for review, or release it. Redemptions are
+    /// rejected while frozen, cancels and refunds are not
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer]` The account of the authority
+    /// 1. `[writable]` The cash_link account holding the cash_link info
+    SetFrozen(SetFrozenArgs),
+    /// Reallocate a cash link created with the legacy layout to the current
+    /// one. Instructions writing the link fail until it is migrated
+    ///
+    ///
+    /// Accounts expected:
+    ///
+    /// 0. `[signer][writable]` The fee payer, paying the rent of the added space
+    /// 1. `[writable]` The cash_link account holding the cash_link info
+    /// 2. `[]` The rent account
+    /// 3. `[]` The system program
+    MigrateCashLink,
+}
+
+/// Trailing `(mint, vault, counterparty)` accounts of a bundle cash link, the
+/// counterparty being `wallet`'s token account or `wallet` for native assets
+fn bundle_account_metas(cash_link: &Pubkey, wallet: &Pubkey, mints: &[Pubkey]) -> Vec<AccountMeta> {
+    let mut accounts = vec![];
+    for mint in mints {
+        accounts.push(AccountMeta::new_readonly(*mint, false));
+        if *mint == spl::native_mint::id() {
+            accounts.push(AccountMeta::new(*cash_link, false));
+            accounts.push(AccountMeta::new(*wallet, false));
+        } else {
+            accounts.push(AccountMeta::new(get_associated_token_address(cash_link, mint), false));
+            accounts.push(AccountMeta::new(get_associated_token_address(wallet, mint), false));
+        }
+    }
+    accounts
+}
+
+/// The stake account of a stake link and the stake program
+fn stake_account_metas(stake: Option<&Pubkey>) -> Vec<AccountMeta> {
+    match stake {
+        Some(key) => vec![
+            AccountMeta::new(*key, false),
+            AccountMeta::new_readonly(stake::program::id(), false),
+        ],
+        None => vec![],
+    }
+}
+
+/// The program config, trailing the accounts of instructions blocked by the
+/// global pause. Those instructions run unpaused until the config is set
+fn config_account_meta(program_id: &Pubkey) -> AccountMeta {
+    AccountMeta::new_readonly(find_config_program_address(program_id).0, false)
+}
+
+/// Create `InitCashLink` instruction
+pub fn init_cash_link(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    owner: &Pubkey,
+    fee_payer: &Pubkey,
+    cash_link_pda: &Pubkey,
+    pass_key: &Pubkey,
+    mint: Option<&Pubkey>,
+    args: InitCashLinkArgs,
+) -> Instruction {
+    let bundle_mints: Vec<Pubkey> = args
+        .bundle
+        .iter()
+        .flatten()
+        .map(|asset| asset.mint)
+        .collect();
+    let owner_key = if mint.is_some()
+        && !args.nft.unwrap_or_default()
+        && !args.fees_in_sol.unwrap_or_default()
+        && args.relayer_fee.unwrap_or_default() == 0
+        && args.refund_bounty.unwrap_or_default() == 0
+        && !bundle_mints.contains(&spl::native_mint::id())
+    {
+        AccountMeta::new_readonly(*owner, true)
+    } else {
+        AccountMeta::new(*owner, true)
+    };
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*authority, true),
+        owner_key,
+        AccountMeta::new(*fee_payer, true),
+        AccountMeta::new(*cash_link_pda, false),
+        AccountMeta::new_readonly(*pass_key, false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::clock::id(), false),
+    ];
+    if let Some(key) = mint {
+        let associated_token_account = get_associated_token_address(cash_link_pda, key);
+        accounts.push(AccountMeta::new_readonly(*key, false));
+        accounts.push(AccountMeta::new(associated_token_account, false));
+        let owner_token_account = get_associated_token_address(owner, key);
+        accounts.push(AccountMeta::new(owner_token_account, false));
+        if let Some(refund_to) = args.refund_to.as_ref() {
+            accounts.push(AccountMeta::new_readonly(get_associated_token_address(refund_to, key), false));
+        }
+        accounts.push(AccountMeta::new_readonly(spl::associated_token::id(), false),);
+        accounts.push(AccountMeta::new(spl::token::id(), false));
+    }
+    accounts.extend(stake_account_metas(args.stake.as_ref()));
+    accounts.extend(bundle_account_metas(cash_link_pda, owner, &bundle_mints));
+    accounts.push(config_account_meta(program_id));
+    Instruction::new_with_borsh(
+        *program_id,
+        &CashInstruction::InitCashLink(args),
+        accounts,
+    )
+}
+
+/// Create `BatchInitCashLinks` instruction for `(cash link, pass key)` pairs
+pub fn batch_init_cash_links(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    owner: &Pubkey,
+    fee_payer: &Pubkey,
+    cash_links: &[(Pubkey, Pubkey)],
+    mint: Option<&Pubkey>,
+    args: BatchInitCashLinksArgs,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new(*owner, true),
+        AccountMeta::new(*fee_payer, true),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::clock::id(), false),
+        AccountMeta::new_readonly(spl::token::id(), false),
+        AccountMeta::new_readonly(spl::associated_token::id(), false),
+    ];
+    if let Some(key) = mint {
+        accounts.push(AccountMeta::new_readonly(*key, false));
+        accounts.push(AccountMeta::new(get_associated_token_address(owner, key), false));
+        if let Some(refund_to) = args.args.refund_to.as_ref() {
+            accounts.push(AccountMeta::new_readonly(get_associated_token_address(refund_to, key), false));
+        }
+    }
+    for (cash_link, pass_key) in cash_links {
+        accounts.push(AccountMeta::new(*cash_link, false));
+        accounts.push(AccountMeta::new_readonly(*pass_key, false));
+        if let Some(key) = mint {
+            accounts.push(AccountMeta::new(get_associated_token_address(cash_link, key), false));
+        }
+    }
+    accounts.push(config_account_meta(program_id));
+    Instruction::new_with_borsh(
+        *program_id,
+        &CashInstruction::BatchInitCashLinks(args),
+        accounts,
+    )
+}
+
+/// Create `Distribute` instruction for `(wallet, redemption)` pairs. `owner`
+/// and `owner_token` belong to the link's refund address
+pub fn distribute(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    cash_link: &Pubkey,
+    pass_key: &Pubkey,
+    fee_token: &Pubkey,
+    owner_token: &Pubkey,
+    owner: Option<&Pubkey>,
+    fee_payer: &Pubkey,
+    mint: Option<&Pubkey>,
+    wallets: &[(Pubkey, Pubkey)],
+    args: DistributeArgs,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new(*cash_link, false),
+        AccountMeta::new_readonly(*pass_key, false),
+        AccountMeta::new(*fee_token, false),
+        AccountMeta::new(*owner_token, false),
+        AccountMeta::new(*fee_payer, true),
+        AccountMeta::new_readonly(sysvar::clock::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(sysvar::slot_hashes::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(spl::token::id(), false),
+        AccountMeta::new_readonly(spl::associated_token::id(), false),
+    ];
+    if let Some(key) = mint {
+        accounts.push(AccountMeta::new(get_associated_token_address(cash_link, key), false));
+        accounts.push(AccountMeta::new_readonly(*key, false));
+    }
+    for (wallet, redemption) in wallets {
+        accounts.push(AccountMeta::new(*wallet, false));
+        accounts.push(AccountMeta::new(*redemption, false));
+        if let Some(key) = mint {
+            accounts.push(AccountMeta::new(get_associated_token_address(wallet, key), false));
+        }
+    }
+    if let Some(key) = owner {
+        accounts.push(AccountMeta::new(*key, false));
+    }
+    accounts.push(config_account_meta(program_id));
+    Instruction::new_with_borsh(
+        *program_id,
+        &CashInstruction::Distribute(args),
+        accounts,
+    )
+}
+
+/// Create `Refund` instruction. `owner` is the link's refund address
+pub fn refund_cash_link(
+    program_id: &Pubkey,
+    cranker: &Pubkey,
+    cash_link: &Pubkey,
+    pass_key: &Pubkey,
+    owner: &Pubkey,
+    mint: Option<&Pubkey>,
+    stake: Option<&Pubkey>,
+    bundle_mints: &[Pubkey],
+    args: RefundArgs,
+) -> Instruction {
+    let owner_token = match mint {
+        Some(key) => get_associated_token_address(owner, key),
+        None => *owner,
+    };
+    let mut accounts = vec![
+        AccountMeta::new(*cranker, true),
+        AccountMeta::new(*cash_link, false),
+        AccountMeta::new_readonly(*pass_key, false),
+        AccountMeta::new(*owner, false),
+        AccountMeta::new(owner_token, false),
+        AccountMeta::new_readonly(sysvar::clock::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+    if let Some(key) = mint {
+        accounts.push(AccountMeta::new(get_associated_token_address(cash_link, key), false));
+    }
+    accounts.push(AccountMeta::new_readonly(spl::token::id(), false));
+    accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+    accounts.extend(stake_account_metas(stake));
+    accounts.extend(bundle_account_metas(cash_link, owner, bundle_mints));
+    Instruction::new_with_borsh(
+        *program_id,
+        &CashInstruction::Refund(args),
+        accounts,
+    )
+}
+
+/// Create `CancelCashLink` instruction. `owner` and `owner_token` belong to
+/// the link's refund address
+pub fn cancel_cash_link(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    cash_link: &Pubkey,
+    pass_key: &Pubkey,
+    owner_token: &Pubkey,
+    vault_token: Option<&Pubkey>,
+    fee_payer: &Pubkey,
+    owner: &Pubkey,
+    stake: Option<&Pubkey>,
+    bundle_mints: &[Pubkey],
+    args: CancelCashRedemptionArgs,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new(*cash_link, false),
+        AccountMeta::new_readonly(*pass_key, false),
+        AccountMeta::new(*owner_token, false),
+        AccountMeta::new(*fee_payer, false),
+        AccountMeta::new_readonly(sysvar::clock::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+
+    if let Some(key) = vault_token {
+        accounts.push(AccountMeta::new(*key, false));
+    }
+
+    accounts.push(AccountMeta::new_readonly(spl::token::id(), false));
+    accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+    accounts.push(AccountMeta::new(*owner, false));
+    accounts.extend(stake_account_metas(stake));
+    accounts.extend(bundle_account_metas(cash_link, owner, bundle_mints));
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &CashInstruction::Cancel(args),
+        accounts,
+    )
+}
+
+/// Create `RedeemCashLink` instruction. `owner` and `owner_token` belong to
+/// the link's refund address, `owner` is required by token links holding a
+/// fee deposit and by bundle links
+pub fn redeem_cash_link(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    wallet: &Pubkey,
+    wallet_token: &Pubkey,
+    collection_fee_token: &Pubkey,
+    vault_token: Option<&Pubkey>,
+    cash_link: &Pubkey,
+    pass_key: &Pubkey,
+    redemption_pda: &Pubkey,
+    owner_token: &Pubkey,
+    owner: Option<&Pubkey>,
+    fee_payer: &Pubkey,
+    fingerprint: Option<&Pubkey>,
+    mint: &Pubkey,
+    stake: Option<&Pubkey>,
+    bundle_mints: &[Pubkey],
+    args: InitCashRedemptionArgs
+) -> Instruction {
+    let pass_key_signs = args.claim_expires_at.is_none() && args.secret.is_none();
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new(*wallet, true),
+        AccountMeta::new(*collection_fee_token, false),
+        AccountMeta::new(*cash_link, false),
+        AccountMeta::new_readonly(*pass_key, pass_key_signs),
+        AccountMeta::new(*redemption_pda, false),
+        AccountMeta::new(*owner_token, false),
+        AccountMeta::new(*fee_payer, true),
+        AccountMeta::new_readonly(sysvar::clock::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(sysvar::slot_hashes::id(), false),
+    ];
+
+    if let Some(key) = vault_token {
+        accounts.push(AccountMeta::new(*wallet_token, false));
+        accounts.push(AccountMeta::new(*key, false));
+        accounts.push(AccountMeta::new_readonly(*mint, false));
+    }
+    accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+    if let Some(fingerprint_id) = fingerprint {
+        accounts.push(AccountMeta::new(*fingerprint_id, false));
+    }
+    accounts.push(AccountMeta::new_readonly(spl::token::id(), false));
+    // also read by links denying programs, which the builder cannot tell
+    accounts.push(AccountMeta::new_readonly(sysvar::instructions::id(), false));
+    if let Some(key) = owner {
+        accounts.push(AccountMeta::new(*key, false));
+        for bundle_mint in bundle_mints.iter().filter(|mint| **mint != spl::native_mint::id()) {
+            accounts.push(AccountMeta::new(get_associated_token_address(key, bundle_mint), false));
+        }
+    }
+    let recipient = args.recipient.unwrap_or(*wallet);
+    if args.recipient.is_some() {
+        accounts.push(AccountMeta::new(recipient, false));
+    }
+    accounts.extend(stake_account_metas(stake));
+    accounts.extend(bundle_account_metas(cash_link, &recipient, bundle_mints));
+    accounts.push(config_account_meta(program_id));
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &CashInstruction::Redeem(args),
+        accounts,
+    )
+}
+
+/// Create `SetConfig` instruction. `program_data` is required when the
+/// config does not exist yet
+pub fn set_config(
+    program_id: &Pubkey,
+    signer: &Pubkey,
+    fee_payer: &Pubkey,
+    program_data: Option<&Pubkey>,
+    args: SetConfigArgs,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*signer, true),
+        AccountMeta::new(find_config_program_address(program_id).0, false),
+        AccountMeta::new(*fee_payer, true),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    if let Some(key) = program_data {
+        accounts.push(AccountMeta::new_readonly(*key, false));
+    }
+    Instruction::new_with_borsh(
+        *program_id,
+        &CashInstruction::SetConfig(args),
+        accounts,
+    )
+}
+
+/// Create `SetFrozen` instruction
+pub fn set_frozen(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    cash_link: &Pubkey,
+    args: SetFrozenArgs,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new(*cash_link, false),
+    ];
+    Instruction::new_with_borsh(
+        *program_id,
+        &CashInstruction::SetFrozen(args),
+        accounts,
+    )
+}
+
+/// Create `MigrateCashLink` instruction
+pub fn migrate_cash_link(program_id: &Pubkey, fee_payer: &Pubkey, cash_link: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*fee_payer, true),
+        AccountMeta::new(*cash_link, false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Instruction::new_with_borsh(
+        *program_id,
+        &CashInstruction::MigrateCashLink,
+        accounts,
+    )
+}
+
+/// Create `CloseCashLink` instruction
+pub fn close_cash_link(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    cash_link: &Pubkey,
+    fee_payer: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new(*cash_link, false),
+        AccountMeta::new(*fee_payer, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &CashInstruction::Close,
+        accounts,
+    )
+}
\ No newline at end of file
@@ -0,0 +1,106 @@
+//! Instructions, accounts and errors of the cash link program
+//!
+//! Everything a client or another on-chain program needs to talk to the
+//! program, without its processor or the SPL program crates.
+pub mod allowlist;
+pub mod cpi;
+pub mod error;
+#[cfg(any(test, feature = "idl"))]
+pub mod idl;
+pub mod instruction;
+pub mod quote;
+pub mod state;
+
+use solana_program::{declare_id, pubkey::Pubkey};
+use state::{cashlink::CashLink, CONFIG_PREFIX, FINGERPRINT_PREFIX, REDEMPTION_PREFIX };
+
+declare_id!("cashQKx31fVsquVKXQ9prKqVtSYf8SqcYt9Jyvg966q");
+
+/// SPL programs and mints referred to by the instructions
+pub mod spl {
+    use solana_program::pubkey::Pubkey;
+
+    pub mod token {
+        solana_program::declare_id!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+    }
+
+    pub mod associated_token {
+        solana_program::declare_id!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+    }
+
+    pub mod native_mint {
+        solana_program::declare_id!("So11111111111111111111111111111111111111112");
+    }
+
+    /// Associated token account of `wallet` for an SPL token `mint`
+    pub fn get_associated_token_address(wallet: &Pubkey, mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[wallet.as_ref(), token::id().as_ref(), mint.as_ref()],
+            &associated_token::id(),
+        )
+        .0
+    }
+}
+
+/// Generates cash link program address
+pub fn find_cash_link_program_address(program_id: &Pubkey, pass_key: Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            CashLink::PREFIX.as_bytes(),
+            pass_key.as_ref()
+        ],
+        program_id,
+    )
+}
+
+/// Generates the address of a hashlocked cash link, whose pass key is the
+/// sha256 of its secret
+pub fn find_hashlock_cash_link_program_address(program_id: &Pubkey, hashlock: &[u8; 32]) -> (Pubkey, u8) {
+    find_cash_link_program_address(program_id, Pubkey::new_from_array(*hashlock))
+}
+
+/// Generates the program config address
+pub fn find_config_program_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONFIG_PREFIX.as_bytes()], program_id)
+}
+
+pub fn find_cash_link_redemption_program_address(program_id: &Pubkey, cash_link: &Pubkey, wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            REDEMPTION_PREFIX.as_bytes(),
+            cash_link.as_ref(),
+            wallet.as_ref()
+        ],
+        program_id,
+    )
+}
+
+/// Generates the fingerprint address of a cash link from the bs58 decoded
+/// fingerprint
+pub fn find_fingerprint_program_address(program_id: &Pubkey, cash_link: &Pubkey, fingerprint: &[u8]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            FINGERPRINT_PREFIX.as_bytes(),
+            cash_link.as_ref(),
+            fingerprint
+        ],
+        program_id,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spl_ids_match_spl_crates() {
+        assert_eq!(spl::token::id(), spl_token::id());
+        assert_eq!(spl::native_mint::id(), spl_token::native_mint::id());
+        assert_eq!(spl::associated_token::id(), spl_associated_token_account::id());
+        let (wallet, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert_eq!(
+            spl::get_associated_token_address(&wallet, &mint),
+            spl_associated_token_account::get_associated_token_address(&wallet, &mint)
+        );
+    }
+}
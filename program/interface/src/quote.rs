@@ -0,0 +1,232 @@
+//! Fee and deposit quotes
+//!
+//! Pure calculations shared by the processor and off-chain clients, so the
+//! amounts quoted to a user are exactly the amounts the program charges.
+use std::convert::TryFrom;
+
+use crate::{error::CashError, instruction::InitCashLinkArgs, state::cashlink::CashLink};
+
+/// Basis points denominator
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Breakdown of the deposit required to initialize a cash link
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DepositQuote {
+    /// Amount distributed to redeemers
+    pub amount: u64,
+    /// Platform fee: the bps fee on `amount` plus the fixed fee
+    pub platform_fee: u64,
+    /// `fee_to_redeem` reserved for every possible redemption
+    pub redemption_fee: u64,
+    /// `relayer_fee` reserved for every possible redemption, always deposited
+    /// in lamports
+    pub relayer_fee: u64,
+    /// Bounty reserved for refunding the link once it expires, deposited in
+    /// lamports
+    pub refund_bounty: u64,
+    /// Total transferred from the owner into the cash link. Links paying
+    /// fees in SOL deposit `amount` in the vault and the fees in lamports
+    pub total: u64,
+}
+
+/// Breakdown of the fees charged by a single redemption
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RedemptionQuote {
+    /// Share of the bps platform fee charged by this redemption
+    pub platform_fee: u64,
+    /// Fixed fee, charged by the first redemption only
+    pub fixed_fee: u64,
+    /// Fee charged for processing this redemption
+    pub fee_to_redeem: u64,
+    /// Sum of all fees charged by this redemption
+    pub total_fee: u64,
+    /// Lamports paid to the fee payer submitting this redemption, on top of
+    /// `total_fee`
+    pub relayer_fee: u64,
+}
+
+impl DepositQuote {
+    /// Split the deposit into the tokens moved to the vault and the lamports
+    /// moved to the cash link account
+    pub fn split(&self, native: bool, fees_in_sol: bool) -> Result<(u64, u64), CashError> {
+        let token_deposit = match (native, fees_in_sol) {
+            (true, _) => 0,
+            (false, true) => self.amount,
+            (false, false) => self
+                .total
+                .checked_sub(self.relayer_fee)
+                .and_then(|total| total.checked_sub(self.refund_bounty))
+                .ok_or(CashError::Underflow)?,
+        };
+        let lamport_deposit = self
+            .total
+            .checked_sub(token_deposit)
+            .ok_or(CashError::Underflow)?;
+        Ok((token_deposit, lamport_deposit))
+    }
+}
+
+/// Fee in basis points of `amount`, rounded down
+pub fn bps_fee(amount: u64, fee_bps: u16) -> Result<u64, CashError> {
+    amount
+        .checked_mul(fee_bps as u64)
+        .ok_or(CashError::Overflow)?
+        .checked_div(BPS_DENOMINATOR)
+        .ok_or(CashError::Overflow)
+}
+
+/// Quote the deposit taken from the owner by `InitCashLink`
+pub fn deposit_quote(args: &InitCashLinkArgs) -> Result<DepositQuote, CashError> {
+    let platform_fee = bps_fee(args.amount, args.fee_bps)?
+        .checked_add(args.fixed_fee)
+        .ok_or(CashError::Overflow)?;
+    let redemption_fee = args
+        .fee_to_redeem
+        .checked_mul(args.max_num_redemptions as u64)
+        .ok_or(CashError::Overflow)?;
+    let relayer_fee = args
+        .relayer_fee
+        .unwrap_or_default()
+        .checked_mul(args.max_num_redemptions as u64)
+        .ok_or(CashError::Overflow)?;
+    let refund_bounty = args.refund_bounty.unwrap_or_default();
+    let total = args
+        .amount
+        .checked_add(platform_fee)
+        .ok_or(CashError::Overflow)?
+        .checked_add(redemption_fee)
+        .ok_or(CashError::Overflow)?
+        .checked_add(relayer_fee)
+        .ok_or(CashError::Overflow)?
+        .checked_add(refund_bounty)
+        .ok_or(CashError::Overflow)?;
+    Ok(DepositQuote {
+        amount: args.amount,
+        platform_fee,
+        redemption_fee,
+        relayer_fee,
+        refund_bounty,
+        total,
+    })
+}
+
+/// Quote the fees charged by the redemption at the zero based `index`.
+///
+/// Every redemption pays an equal, rounded down share of the bps platform fee
+/// and the `last` one, the redemption that leaves the link fully redeemed,
+/// settles whatever is still owed so the fees collected add up to the fee
+/// deposited at init.
+pub fn redemption_quote(
+    cash_link: &CashLink,
+    index: u16,
+    last: bool,
+) -> Result<RedemptionQuote, CashError> {
+    if index >= cash_link.max_num_redemptions {
+        return Err(CashError::MaxRedemptionsReached);
+    }
+    let total_platform_fee = bps_fee(cash_link.amount, cash_link.fee_bps)?;
+    let platform_fee = if last {
+        total_platform_fee
+            .checked_sub(cash_link.fees_collected)
+            .ok_or(CashError::Underflow)?
+    } else {
+        total_platform_fee
+            .checked_div(cash_link.max_num_redemptions as u64)
+            .ok_or(CashError::Overflow)?
+    };
+    let fixed_fee = if index == 0 { cash_link.fixed_fee } else { 0 };
+    let total_fee = platform_fee
+        .checked_add(cash_link.fee_to_redeem)
+        .ok_or(CashError::Overflow)?
+        .checked_add(fixed_fee)
+        .ok_or(CashError::Overflow)?;
+    Ok(RedemptionQuote {
+        platform_fee,
+        fixed_fee,
+        fee_to_redeem: cash_link.fee_to_redeem,
+        total_fee,
+        relayer_fee: cash_link.relayer_fee,
+    })
+}
+
+/// Share of a bundle asset paid with `amount_to_redeem`, in proportion to the
+/// link's `remaining_amount` before the redemption. Redeeming all of the
+/// remaining amount takes whatever is left of the asset, rounding dust
+/// included
+pub fn bundle_share(
+    asset_remaining: u64,
+    amount_to_redeem: u64,
+    remaining_amount: u64,
+) -> Result<u64, CashError> {
+    if remaining_amount == 0 {
+        return Ok(0);
+    }
+    let share = (asset_remaining as u128)
+        .checked_mul(amount_to_redeem as u128)
+        .ok_or(CashError::Overflow)?
+        .checked_div(remaining_amount as u128)
+        .ok_or(CashError::Overflow)?;
+    u64::try_from(share).map_err(|_| CashError::Overflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cash_link(amount: u64, fee_bps: u16, max_num_redemptions: u16) -> CashLink {
+        CashLink {
+            amount,
+            fee_bps,
+            fixed_fee: 7,
+            fee_to_redeem: 3,
+            max_num_redemptions,
+            ..CashLink::default()
+        }
+    }
+
+    fn collect_fees(cash_link: &mut CashLink, redemptions: u16) -> u64 {
+        for index in 0..redemptions {
+            let quote = redemption_quote(cash_link, index, index + 1 == redemptions).unwrap();
+            cash_link.fees_collected += quote.platform_fee;
+        }
+        cash_link.fees_collected
+    }
+
+    #[test]
+    fn test_fees_collected_equal_fee_deposited() {
+        for (amount, fee_bps, max_num_redemptions) in
+            [(1_000_003, 250, 7), (999, 33, 10), (10_000, 100, 1), (12_345_678, 9_999, 13)]
+        {
+            let mut cash_link = cash_link(amount, fee_bps, max_num_redemptions);
+            let deposited = bps_fee(amount, fee_bps).unwrap();
+            assert_eq!(collect_fees(&mut cash_link, max_num_redemptions), deposited);
+        }
+    }
+
+    #[test]
+    fn test_fees_collected_when_fully_redeemed_early() {
+        let mut cash_link = cash_link(1_000_003, 250, 7);
+        let deposited = bps_fee(1_000_003, 250).unwrap();
+        assert_eq!(collect_fees(&mut cash_link, 4), deposited);
+    }
+
+    #[test]
+    fn test_bundle_share_is_proportional() {
+        assert_eq!(bundle_share(100, 250, 1_000), Ok(25));
+        assert_eq!(bundle_share(100, 333, 1_000), Ok(33));
+        // the dust left by earlier shares goes to whoever redeems the rest
+        assert_eq!(bundle_share(67, 667, 667), Ok(67));
+        assert_eq!(bundle_share(100, 1, 0), Ok(0));
+    }
+
+    #[test]
+    fn test_fixed_fee_charged_once() {
+        let cash_link = cash_link(10_000, 100, 4);
+        assert_eq!(redemption_quote(&cash_link, 0, false).unwrap().total_fee, 25 + 3 + 7);
+        assert_eq!(redemption_quote(&cash_link, 1, false).unwrap().total_fee, 25 + 3);
+        assert_eq!(
+            redemption_quote(&cash_link, 4, true),
+            Err(CashError::MaxRedemptionsReached)
+        );
+    }
+}
@@ -1,22 +1,25 @@
 use borsh::{BorshDeserialize, BorshSerialize, BorshSchema};
 
 pub mod cashlink;
+pub mod config;
 pub mod redemption;
+pub mod transitions;
 
 pub const FLAG_ACCOUNT_SIZE: usize = 1;
-pub const FINGERPRINT_PREFIX: &'static str = "fingerprint";
-pub const REDEMPTION_PREFIX: &'static str = "redeem";
+pub const FINGERPRINT_PREFIX: &str = "fingerprint";
+pub const REDEMPTION_PREFIX: &str = "redeem";
+pub const CONFIG_PREFIX: &str = "config";
 /// Enum representing the account type managed by the program
-#[derive(Clone, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema)]
+#[derive(Clone, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema, Default)]
 pub enum AccountType {
     /// If the account has not been initialized, the enum will be 0
+    #[default]
     Uninitialized,
     /// A cashlink account type
     CashLink,
+    /// A wallet's claims on a cashlink
+    Redemption,
+    /// The program config
+    Config,
 }
 
-impl Default for AccountType {
-    fn default() -> Self {
-        AccountType::Uninitialized
-    }
-}
\ No newline at end of file
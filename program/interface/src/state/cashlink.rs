@@ -0,0 +1,294 @@
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use solana_program::{
+    borsh0_10::try_from_slice_unchecked,
+    msg,
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+use crate::error::CashError;
+
+use super::AccountType;
+
+pub const MAX_BUNDLE_ASSETS: usize = 4;
+pub const BUNDLE_ASSET_SIZE: usize = 48;
+pub const MAX_DENIED_PROGRAMS: usize = 4;
+pub const CASH_LINK_DATA_SIZE: usize =
+    391 + MAX_BUNDLE_ASSETS * BUNDLE_ASSET_SIZE + MAX_DENIED_PROGRAMS * 32;
+/// Size of the cash links created before the fields following `pass_key`
+/// were added. Fields are only ever appended, so these accounts still read
+/// as a prefix of the current layout and are reallocated in place by
+/// `MigrateCashLink` before they can be written again
+pub const LEGACY_CASH_LINK_DATA_SIZE: usize = 196;
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq, Clone, Default)]
+pub enum CashLinkState {
+    #[default]
+    Initialized = 0,
+    Redeemed,
+    Redeeming,
+    Expired,
+    /// Held by the authority for review. Redemptions are rejected while
+    /// cancels and refunds remain possible
+    Frozen,
+    /// Cancelled by the authority, as opposed to refunded once expired
+    Cancelled,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq, Clone, Default)]
+pub enum DistributionType {
+    #[default]
+    Fixed = 0,
+    Random,
+}
+
+/// An extra asset escrowed with the link and paid out alongside its amount.
+/// The native mint stands for lamports held by the cash link account itself
+#[repr(C)]
+#[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema, Default)]
+pub struct BundleAsset {
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub remaining_amount: u64,
+}
+
+impl BundleAsset {
+    pub fn is_native(&self) -> bool {
+        self.mint == crate::spl::native_mint::id()
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema, Default)]
+pub struct CashLink {
+    pub account_type: AccountType,
+    pub authority: Pubkey,
+    pub state: CashLinkState,
+    pub amount: u64,
+    pub fee_bps: u16,
+    pub fixed_fee: u64,
+    pub fee_to_redeem: u64,
+    pub remaining_amount: u64,
+    pub distribution_type: DistributionType,
+    pub owner: Pubkey,
+    pub last_redeemed_at: Option<u64>,
+    pub expires_at: u64,
+    pub mint: Option<Pubkey>,
+    pub total_redemptions: u16,
+    pub max_num_redemptions: u16,
+    pub min_amount: u64,
+    pub fingerprint_enabled: bool,
+    pub pass_key: Pubkey,
+    /// Platform bps fees paid out by redemptions so far
+    pub fees_collected: u64,
+    /// Merkle root of the wallets allowed to redeem, if restricted
+    pub allowlist_root: Option<[u8; 32]>,
+    /// sha256 of the secret unlocking the link, in place of the pass key
+    /// signature. The pass key of a hashlocked link is the hash itself, so a
+    /// short secret can be brute-forced from it
+    pub hashlock: Option<[u8; 32]>,
+    pub max_claims_per_wallet: u16,
+    /// Seconds between any two redemptions, 0 when disabled
+    pub cooldown_seconds: u64,
+    /// Seconds between two claims by the same wallet, 0 when disabled
+    pub wallet_cooldown_seconds: u64,
+    /// Assets paid out in proportion to `amount`, at most `MAX_BUNDLE_ASSETS`
+    pub bundle: Vec<BundleAsset>,
+    /// Single-item link gifting an NFT
+    pub nft: bool,
+    /// Fees are deposited as lamports on the cash link and paid in SOL
+    /// instead of being taken from the token vault
+    pub fees_in_sol: bool,
+    /// Lamports paid to the fee payer of every redemption
+    pub relayer_fee: u64,
+    /// Lamports paid to whoever refunds the link once it expires
+    pub refund_bounty: u64,
+    /// Time the link was cancelled, or refunded once expired
+    pub ended_at: Option<u64>,
+    /// Reason code given by the authority when cancelling, 0 if unspecified
+    pub cancel_reason: u8,
+    /// Redemptions must be top-level instructions, not invoked through CPI
+    pub forbid_cpi: bool,
+    /// Programs whose instructions may not appear in a redemption
+    /// transaction, at most `MAX_DENIED_PROGRAMS`
+    pub denied_programs: Vec<Pubkey>,
+    /// Stake account gifted by a stake link, its authorities held by the link
+    pub stake: Option<Pubkey>,
+    /// Wallet refunded in place of the owner on cancel, refund and full
+    /// redemption, such as a treasury or multisig vault
+    pub refund_to: Option<Pubkey>,
+}
+
+impl CashLink {
+    pub const PREFIX: &'static str = "cash";
+    pub fn redeemed(&self) -> bool {
+        self.state == CashLinkState::Redeemed
+    }
+    pub fn redeeming(&self) -> bool {
+        self.state == CashLinkState::Redeeming
+    }
+    pub fn expired(&self) -> bool {
+        self.state == CashLinkState::Expired
+    }
+    pub fn cancelled(&self) -> bool {
+        self.state == CashLinkState::Cancelled
+    }
+    pub fn frozen(&self) -> bool {
+        self.state == CashLinkState::Frozen
+    }
+    pub fn initialized(&self) -> bool {
+        self.state == CashLinkState::Initialized
+    }
+    /// The wallet refunded what is left of the link, `refund_to` if set
+    pub fn refund_address(&self) -> &Pubkey {
+        self.refund_to.as_ref().unwrap_or(&self.owner)
+    }
+    pub fn is_fully_redeemed(&self) -> Result<bool, CashError> {
+        Ok(self.total_redemptions == self.max_num_redemptions
+            || self.remaining_amount == 0
+            || self.remaining_amount < self.min_total_required()?)
+    }
+
+    pub fn max_num_redemptions_remaining(&self) -> Result<u16, CashError> {
+        self.max_num_redemptions
+            .checked_sub(self.total_redemptions)
+            .ok_or(CashError::Overflow)
+    }
+
+    /// Whether a token link holds a fee deposit in lamports
+    pub fn has_fee_deposit(&self) -> bool {
+        self.mint.is_some() && (self.fees_in_sol || self.relayer_fee > 0 || self.refund_bounty > 0)
+    }
+
+    /// Lamports of native bundle assets still held by the cash link
+    pub fn native_bundle_remaining(&self) -> Result<u64, CashError> {
+        self.bundle
+            .iter()
+            .filter(|asset| asset.is_native())
+            .try_fold(0u64, |total, asset| {
+                total
+                    .checked_add(asset.remaining_amount)
+                    .ok_or(CashError::Overflow)
+            })
+    }
+
+    pub fn min_total_required(&self) -> Result<u64, CashError> {
+        Ok(self.min_amount * self.max_num_redemptions_remaining()? as u64)
+    }
+}
+
+impl IsInitialized for CashLink {
+    fn is_initialized(&self) -> bool {
+        self.initialized() || self.redeeming() || self.redeemed() || self.expired() || self.frozen()
+            || self.cancelled()
+    }
+}
+
+impl Sealed for CashLink {}
+
+impl Pack for CashLink {
+    const LEN: usize = CASH_LINK_DATA_SIZE;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let mut slice = dst;
+        self.serialize(&mut slice).unwrap()
+    }
+
+    /// Accepts the legacy layout as well, the length is checked by
+    /// `unpack_from_slice`
+    fn unpack_unchecked(input: &[u8]) -> Result<Self, ProgramError> {
+        Self::unpack_from_slice(input)
+    }
+
+    /// Legacy links read with their missing fields zeroed, except for the
+    /// single claim per wallet they were limited to. Packing one fails on
+    /// its length until it is migrated
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        match src.len() {
+            Self::LEN => Ok(try_from_slice_unchecked(src)?),
+            LEGACY_CASH_LINK_DATA_SIZE => {
+                let mut data = vec![0; Self::LEN];
+                data[..src.len()].copy_from_slice(src);
+                let mut result: Self = try_from_slice_unchecked(&data)?;
+                result.max_claims_per_wallet = 1;
+                Ok(result)
+            }
+            _ => {
+                msg!("Failed to deserialize");
+                Err(ProgramError::InvalidAccountData)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A link holding only the fields of the legacy layout
+    fn legacy_link(mint: Option<Pubkey>) -> CashLink {
+        CashLink {
+            account_type: AccountType::CashLink,
+            authority: Pubkey::new_unique(),
+            state: CashLinkState::Redeeming,
+            amount: 1_000,
+            fee_bps: 10,
+            fixed_fee: 5,
+            fee_to_redeem: 5,
+            remaining_amount: 500,
+            distribution_type: DistributionType::Random,
+            owner: Pubkey::new_unique(),
+            last_redeemed_at: Some(1),
+            expires_at: 2,
+            mint,
+            total_redemptions: 1,
+            max_num_redemptions: 2,
+            min_amount: 100,
+            fingerprint_enabled: true,
+            pass_key: Pubkey::new_unique(),
+            ..CashLink::default()
+        }
+    }
+
+    #[test]
+    fn test_unpack_legacy_layout() {
+        for mint in [Some(Pubkey::new_unique()), None] {
+            let link = legacy_link(mint);
+            let mut data = vec![0; CashLink::LEN];
+            link.pack_into_slice(&mut data);
+            let legacy = &data[..LEGACY_CASH_LINK_DATA_SIZE];
+            assert_eq!(
+                CashLink::unpack(legacy).unwrap(),
+                CashLink {
+                    max_claims_per_wallet: 1,
+                    ..link.clone()
+                }
+            );
+            let mut legacy = legacy.to_vec();
+            assert_eq!(
+                CashLink::pack(link, &mut legacy),
+                Err(ProgramError::InvalidAccountData)
+            );
+        }
+    }
+
+    #[test]
+    fn test_legacy_layout_ends_at_pass_key() {
+        let link = CashLink {
+            fees_collected: u64::MAX,
+            ..legacy_link(Some(Pubkey::new_unique()))
+        };
+        let data = link.try_to_vec().unwrap();
+        assert_eq!(
+            data[LEGACY_CASH_LINK_DATA_SIZE - 32..LEGACY_CASH_LINK_DATA_SIZE],
+            link.pass_key.to_bytes()
+        );
+        assert_eq!(
+            data[LEGACY_CASH_LINK_DATA_SIZE..LEGACY_CASH_LINK_DATA_SIZE + 8],
+            u64::MAX.to_le_bytes()
+        );
+    }
+}
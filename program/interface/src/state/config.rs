@@ -0,0 +1,56 @@
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use solana_program::{
+    borsh0_10::try_from_slice_unchecked,
+    msg,
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+use super::AccountType;
+
+pub const CONFIG_SIZE: usize = 34;
+
+/// Program-wide settings, held by the single PDA derived from `Config::PREFIX`
+#[repr(C)]
+#[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema, Default)]
+pub struct Config {
+    pub account_type: AccountType,
+    /// Key allowed to update the config
+    pub admin: Pubkey,
+    /// Rejects every instruction creating or redeeming cash links while set.
+    /// Cancels and refunds remain possible
+    pub paused: bool,
+}
+
+impl Config {
+    pub const PREFIX: &'static str = super::CONFIG_PREFIX;
+}
+
+impl IsInitialized for Config {
+    fn is_initialized(&self) -> bool {
+        self.account_type == AccountType::Config
+    }
+}
+
+impl Sealed for Config {}
+
+impl Pack for Config {
+    const LEN: usize = CONFIG_SIZE;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let mut slice = dst;
+        self.serialize(&mut slice).unwrap()
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if src.len() != Self::LEN {
+            msg!("Failed to deserialize");
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let result: Self = try_from_slice_unchecked(src)?;
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,154 @@
+//! Cash link state machine
+//!
+//! Every processor changing the state of a cash link goes through
+//! `transition`, so the allowed moves between `CashLinkState` values are
+//! defined in one place.
+//!
+//! ```text
+//! Initialized --Redeem--> Redeeming --Settle--> Redeemed
+//! Initialized | Redeeming --Freeze--> Frozen --Unfreeze--> Initialized | Redeeming
+//! Initialized | Redeeming | Frozen --Cancel--> Cancelled
+//! Initialized | Redeeming | Frozen --Refund--> Expired
+//! ```
+use crate::error::CashError;
+
+use super::cashlink::CashLinkState;
+
+/// Something happening to a cash link
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CashLinkEvent {
+    /// A redemption starts paying out
+    Redeem,
+    /// A redemption left the link fully redeemed
+    Settle,
+    /// The authority cancels the link
+    Cancel,
+    /// The link is refunded once expired
+    Refund,
+    /// The authority freezes the link for review
+    Freeze,
+    /// The authority releases a frozen link, `has_redemptions` deciding the
+    /// state it returns to
+    Unfreeze { has_redemptions: bool },
+    /// The link account is closed, which requires it to be over
+    Close,
+}
+
+/// The state a cash link moves to from `from` on `event`
+pub fn transition(from: &CashLinkState, event: CashLinkEvent) -> Result<CashLinkState, CashError> {
+    use CashLinkEvent::*;
+    use CashLinkState::*;
+
+    match (from, event) {
+        (Expired, Close) | (Cancelled, Close) => Ok(from.clone()),
+        (_, Close) => Err(CashError::AccountNotExpired),
+        (Expired, _) => Err(CashError::AccountAlreadyExpired),
+        (Cancelled, _) => Err(CashError::AccountAlreadyCancelled),
+        (Redeemed, _) => Err(CashError::AccountAlreadyRedeemed),
+        (Frozen, Unfreeze { has_redemptions: true }) => Ok(Redeeming),
+        (Frozen, Unfreeze { has_redemptions: false }) => Ok(Initialized),
+        (_, Unfreeze { .. }) => Err(CashError::AccountNotFrozen),
+        (Frozen, Cancel) => Ok(Cancelled),
+        (Frozen, Refund) => Ok(Expired),
+        (Frozen, _) => Err(CashError::AccountFrozen),
+        (Initialized, Redeem) | (Redeeming, Redeem) => Ok(Redeeming),
+        (Redeeming, Settle) => Ok(Redeemed),
+        (Initialized, Settle) => Err(CashError::AccountInvalidState),
+        (Initialized, Freeze) | (Redeeming, Freeze) => Ok(Frozen),
+        (Initialized, Cancel) | (Redeeming, Cancel) => Ok(Cancelled),
+        (Initialized, Refund) | (Redeeming, Refund) => Ok(Expired),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use CashLinkEvent::*;
+    use CashLinkState::*;
+
+    /// Every `(state, event)` pair and what `transition` makes of it
+    const TRANSITIONS: [(CashLinkState, CashLinkEvent, Result<CashLinkState, CashError>); 48] = [
+        (Initialized, Redeem, Ok(Redeeming)),
+        (Initialized, Settle, Err(CashError::AccountInvalidState)),
+        (Initialized, Cancel, Ok(Cancelled)),
+        (Initialized, Refund, Ok(Expired)),
+        (Initialized, Freeze, Ok(Frozen)),
+        (Initialized, Unfreeze { has_redemptions: false }, Err(CashError::AccountNotFrozen)),
+        (Initialized, Unfreeze { has_redemptions: true }, Err(CashError::AccountNotFrozen)),
+        (Initialized, Close, Err(CashError::AccountNotExpired)),
+        (Redeemed, Redeem, Err(CashError::AccountAlreadyRedeemed)),
+        (Redeemed, Settle, Err(CashError::AccountAlreadyRedeemed)),
+        (Redeemed, Cancel, Err(CashError::AccountAlreadyRedeemed)),
+        (Redeemed, Refund, Err(CashError::AccountAlreadyRedeemed)),
+        (Redeemed, Freeze, Err(CashError::AccountAlreadyRedeemed)),
+        (Redeemed, Unfreeze { has_redemptions: false }, Err(CashError::AccountAlreadyRedeemed)),
+        (Redeemed, Unfreeze { has_redemptions: true }, Err(CashError::AccountAlreadyRedeemed)),
+        (Redeemed, Close, Err(CashError::AccountNotExpired)),
+        (Redeeming, Redeem, Ok(Redeeming)),
+        (Redeeming, Settle, Ok(Redeemed)),
+        (Redeeming, Cancel, Ok(Cancelled)),
+        (Redeeming, Refund, Ok(Expired)),
+        (Redeeming, Freeze, Ok(Frozen)),
+        (Redeeming, Unfreeze { has_redemptions: false }, Err(CashError::AccountNotFrozen)),
+        (Redeeming, Unfreeze { has_redemptions: true }, Err(CashError::AccountNotFrozen)),
+        (Redeeming, Close, Err(CashError::AccountNotExpired)),
+        (Expired, Redeem, Err(CashError::AccountAlreadyExpired)),
+        (Expired, Settle, Err(CashError::AccountAlreadyExpired)),
+        (Expired, Cancel, Err(CashError::AccountAlreadyExpired)),
+        (Expired, Refund, Err(CashError::AccountAlreadyExpired)),
+        (Expired, Freeze, Err(CashError::AccountAlreadyExpired)),
+        (Expired, Unfreeze { has_redemptions: false }, Err(CashError::AccountAlreadyExpired)),
+        (Expired, Unfreeze { has_redemptions: true }, Err(CashError::AccountAlreadyExpired)),
+        (Expired, Close, Ok(Expired)),
+        (Frozen, Redeem, Err(CashError::AccountFrozen)),
+        (Frozen, Settle, Err(CashError::AccountFrozen)),
+        (Frozen, Cancel, Ok(Cancelled)),
+        (Frozen, Refund, Ok(Expired)),
+        (Frozen, Freeze, Err(CashError::AccountFrozen)),
+        (Frozen, Unfreeze { has_redemptions: false }, Ok(Initialized)),
+        (Frozen, Unfreeze { has_redemptions: true }, Ok(Redeeming)),
+        (Frozen, Close, Err(CashError::AccountNotExpired)),
+        (Cancelled, Redeem, Err(CashError::AccountAlreadyCancelled)),
+        (Cancelled, Settle, Err(CashError::AccountAlreadyCancelled)),
+        (Cancelled, Cancel, Err(CashError::AccountAlreadyCancelled)),
+        (Cancelled, Refund, Err(CashError::AccountAlreadyCancelled)),
+        (Cancelled, Freeze, Err(CashError::AccountAlreadyCancelled)),
+        (Cancelled, Unfreeze { has_redemptions: false }, Err(CashError::AccountAlreadyCancelled)),
+        (Cancelled, Unfreeze { has_redemptions: true }, Err(CashError::AccountAlreadyCancelled)),
+        (Cancelled, Close, Ok(Cancelled)),
+    ];
+
+    #[test]
+    fn test_every_transition() {
+        for (from, event, to) in TRANSITIONS.iter() {
+            assert_eq!(transition(from, *event), *to, "{:?} on {:?}", from, event);
+        }
+    }
+
+    #[test]
+    fn test_transition_table_is_complete() {
+        for (i, (from, event, _)) in TRANSITIONS.iter().enumerate() {
+            assert!(
+                TRANSITIONS[..i].iter().all(|(state, e, _)| state != from || e != event),
+                "{:?} on {:?} is listed twice",
+                from,
+                event
+            );
+        }
+    }
+
+    #[test]
+    fn test_redeem_then_settle() {
+        let state = transition(&Initialized, Redeem).unwrap();
+        assert_eq!(transition(&state, Settle), Ok(Redeemed));
+        assert_eq!(transition(&Redeemed, Redeem), Err(CashError::AccountAlreadyRedeemed));
+    }
+
+    #[test]
+    fn test_frozen_link_can_only_end_or_unfreeze() {
+        assert_eq!(transition(&Frozen, Redeem), Err(CashError::AccountFrozen));
+        assert_eq!(transition(&Frozen, Freeze), Err(CashError::AccountFrozen));
+        assert_eq!(transition(&Frozen, Cancel), Ok(Cancelled));
+        assert_eq!(transition(&Frozen, Close), Err(CashError::AccountNotExpired));
+    }
+}
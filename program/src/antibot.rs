@@ -0,0 +1,81 @@
+//! Anti-bot restrictions on redemptions
+//!
+//! Bots claim random links from their own programs and revert unless the drawn
+//! amount is large. A link may require its redemptions to be top-level
+//! instructions and reject transactions calling programs it denies, found
+//! through the instructions sysvar.
+use solana_program::{
+    account_info::AccountInfo,
+    instruction::{get_stack_height, TRANSACTION_LEVEL_STACK_HEIGHT},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::instructions::load_instruction_at_checked,
+};
+
+use crate::error::CashError;
+
+/// Assert the current instruction is not invoked through CPI
+pub fn assert_not_cpi() -> Result<(), ProgramError> {
+    if get_stack_height() > TRANSACTION_LEVEL_STACK_HEIGHT {
+        return Err(CashError::RedemptionViaCpi.into());
+    }
+    Ok(())
+}
+
+/// Assert no instruction of the transaction calls a `denied` program
+pub fn assert_no_denied_programs(
+    instructions_info: &AccountInfo,
+    denied: &[Pubkey],
+) -> Result<(), ProgramError> {
+    let mut index = 0;
+    while let Ok(instruction) = load_instruction_at_checked(index, instructions_info) {
+        if denied.contains(&instruction.program_id) {
+            return Err(CashError::DeniedProgramInTransaction.into());
+        }
+        index += 1;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_program::sysvar::{
+        self,
+        instructions::{construct_instructions_data, BorrowedInstruction},
+    };
+
+    fn check(program_ids: &[Pubkey], denied: &[Pubkey]) -> Result<(), ProgramError> {
+        let instructions: Vec<_> = program_ids
+            .iter()
+            .map(|program_id| BorrowedInstruction {
+                program_id,
+                accounts: vec![],
+                data: &[],
+            })
+            .collect();
+        let mut data = construct_instructions_data(&instructions);
+        let mut lamports = 0;
+        let key = sysvar::instructions::id();
+        let owner = sysvar::id();
+        let info = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        assert_no_denied_programs(&info, denied)
+    }
+
+    #[test]
+    fn test_denied_programs() {
+        let cash = crate::id();
+        let bot = Pubkey::new_unique();
+        assert!(check(&[cash], &[bot]).is_ok());
+        assert!(check(&[cash, Pubkey::new_unique()], &[bot]).is_ok());
+        assert!(check(&[cash, bot], &[]).is_ok());
+        assert_eq!(
+            check(&[cash, bot], &[bot]),
+            Err(CashError::DeniedProgramInTransaction.into())
+        );
+        assert_eq!(
+            check(&[bot, cash], &[Pubkey::new_unique(), bot]),
+            Err(CashError::DeniedProgramInTransaction.into())
+        );
+    }
+}
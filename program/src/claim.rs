@@ -0,0 +1,224 @@
+//! Signed claim authorization
+//!
+//! Instead of signing the redemption transaction, the pass key signs a claim
+//! message binding the cash link, the redeeming wallet, the number of claims
+//! it has made so far and an expiry, so a signature authorizes a single claim.
+//! The signature is checked by the Ed25519 precompile in the same transaction
+//! and the program finds that instruction through the instructions sysvar.
+use solana_program::{
+    account_info::AccountInfo, ed25519_program, program_error::ProgramError, pubkey::Pubkey,
+    sysvar::instructions::load_instruction_at_checked,
+};
+
+use crate::{error::CashError, utils::cmp_pubkeys};
+
+pub const CLAIM_PREFIX: &str = "claim";
+
+const SIGNATURE_OFFSETS_START: usize = 2;
+const SIGNATURE_OFFSETS_SIZE: usize = 14;
+/// Instruction index marking data held by the Ed25519 instruction itself
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// Message the pass key signs to authorize the claim of `wallet` made after
+/// `total_claims` others
+pub fn claim_message(
+    cash_link: &Pubkey,
+    wallet: &Pubkey,
+    total_claims: u16,
+    expires_at: u64,
+) -> Vec<u8> {
+    [
+        CLAIM_PREFIX.as_bytes(),
+        cash_link.as_ref(),
+        wallet.as_ref(),
+        &total_claims.to_le_bytes(),
+        &expires_at.to_le_bytes(),
+    ]
+    .concat()
+}
+
+/// Assert the transaction carries an Ed25519 instruction verifying `message`
+/// signed by `signer`
+pub fn assert_claim_signed(
+    instructions_info: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<(), ProgramError> {
+    let mut index = 0;
+    while let Ok(instruction) = load_instruction_at_checked(index, instructions_info) {
+        if cmp_pubkeys(&instruction.program_id, &ed25519_program::id())
+            && verifies(&instruction.data, signer, message)
+        {
+            return Ok(());
+        }
+        index += 1;
+    }
+    Err(CashError::ClaimSignatureNotFound.into())
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+/// Whether the Ed25519 instruction `data` verifies a single signature by
+/// `signer` over exactly `message`
+fn verifies(data: &[u8], signer: &Pubkey, message: &[u8]) -> bool {
+    matches!(
+        signed_message(data),
+        Some((public_key, signed)) if public_key == signer.as_ref() && signed == message
+    )
+}
+
+/// Public key and message of an Ed25519 instruction holding one signature
+/// with all of its data inline
+fn signed_message(data: &[u8]) -> Option<(&[u8], &[u8])> {
+    if data.first() != Some(&1) || data.len() < SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_SIZE {
+        return None;
+    }
+    let field = |i: usize| read_u16(data, SIGNATURE_OFFSETS_START + 2 * i);
+    // signature, public key and message must all live in this instruction
+    for instruction_index in [field(1)?, field(3)?, field(6)?] {
+        if instruction_index != CURRENT_INSTRUCTION {
+            return None;
+        }
+    }
+    let public_key_offset = field(2)? as usize;
+    let message_offset = field(4)? as usize;
+    let message_size = field(5)? as usize;
+    Some((
+        data.get(public_key_offset..public_key_offset + 32)?,
+        data.get(message_offset..message_offset + message_size)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_program::{
+        instruction::Instruction,
+        sysvar::{
+            self,
+            instructions::{construct_instructions_data, BorrowedInstruction},
+        },
+    };
+    use solana_sdk::{
+        ed25519_instruction::{new_ed25519_instruction, verify},
+        feature_set::FeatureSet,
+        signature::{Keypair, Signer},
+    };
+
+    fn ed25519_instruction(signer: &Keypair, message: &[u8]) -> Instruction {
+        let keypair = ed25519_dalek::Keypair::from_bytes(&signer.to_bytes()).unwrap();
+        new_ed25519_instruction(&keypair, message)
+    }
+
+    /// Merge single signature Ed25519 instructions into one verifying all of
+    /// their signatures
+    fn merge(instructions: &[Instruction]) -> Instruction {
+        let header = SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_SIZE * instructions.len();
+        let mut offsets = vec![instructions.len() as u8, 0];
+        let mut payload = vec![];
+        for instruction in instructions {
+            let start = SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_SIZE;
+            let shift = (header + payload.len() - start) as u16;
+            for (i, field) in instruction.data[SIGNATURE_OFFSETS_START..start].chunks(2).enumerate() {
+                let field = u16::from_le_bytes([field[0], field[1]]);
+                // the signature, public key and message offsets move with the data
+                let field = if i % 2 == 0 && i < 6 { field + shift } else { field };
+                offsets.extend_from_slice(&field.to_le_bytes());
+            }
+            payload.extend_from_slice(&instruction.data[start..]);
+        }
+        Instruction {
+            program_id: ed25519_program::id(),
+            accounts: vec![],
+            data: [offsets, payload].concat(),
+        }
+    }
+
+    fn check(instruction: &Instruction, signer: &Pubkey, message: &[u8]) -> Result<(), ProgramError> {
+        let cash = crate::id();
+        let instructions = [
+            BorrowedInstruction {
+                program_id: &instruction.program_id,
+                accounts: vec![],
+                data: &instruction.data,
+            },
+            BorrowedInstruction {
+                program_id: &cash,
+                accounts: vec![],
+                data: &[],
+            },
+        ];
+        let mut data = construct_instructions_data(&instructions);
+        let mut lamports = 0;
+        let key = sysvar::instructions::id();
+        let owner = sysvar::id();
+        let info = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        assert_claim_signed(&info, signer, message)
+    }
+
+    #[test]
+    fn test_claim_signed() {
+        let pass_key = Keypair::new();
+        let message = claim_message(&Pubkey::new_unique(), &Pubkey::new_unique(), 0, 1_000);
+        let instruction = ed25519_instruction(&pass_key, &message);
+        assert!(check(&instruction, &pass_key.pubkey(), &message).is_ok());
+
+        let not_found = Err(CashError::ClaimSignatureNotFound.into());
+        let other = Keypair::new();
+        let signed_by_other = ed25519_instruction(&other, &message);
+        assert_eq!(check(&signed_by_other, &pass_key.pubkey(), &message), not_found);
+        let mut other_message = message.clone();
+        other_message[message.len() - 1] ^= 1;
+        assert_eq!(check(&instruction, &pass_key.pubkey(), &other_message), not_found);
+        assert_eq!(check(&instruction, &pass_key.pubkey(), &message[1..]), not_found);
+
+        let mut not_ed25519 = instruction.clone();
+        not_ed25519.program_id = Pubkey::new_unique();
+        assert_eq!(check(&not_ed25519, &pass_key.pubkey(), &message), not_found);
+    }
+
+    #[test]
+    fn test_claim_data_must_be_inline() {
+        let pass_key = Keypair::new();
+        let message = claim_message(&Pubkey::new_unique(), &Pubkey::new_unique(), 0, 1_000);
+        // signature, public key and message instruction indices
+        for field in [1, 3, 6] {
+            let mut instruction = ed25519_instruction(&pass_key, &message);
+            let offset = SIGNATURE_OFFSETS_START + 2 * field;
+            instruction.data[offset..offset + 2].copy_from_slice(&0u16.to_le_bytes());
+            assert_eq!(
+                check(&instruction, &pass_key.pubkey(), &message),
+                Err(CashError::ClaimSignatureNotFound.into())
+            );
+        }
+    }
+
+    #[test]
+    fn test_claim_signed_among_several_signatures() {
+        let pass_key = Keypair::new();
+        let message = claim_message(&Pubkey::new_unique(), &Pubkey::new_unique(), 0, 1_000);
+        let instruction = merge(&[
+            ed25519_instruction(&Keypair::new(), b"other message"),
+            ed25519_instruction(&pass_key, &message),
+        ]);
+        assert_eq!(instruction.data[0], 2);
+        assert!(verify(&instruction.data, &[&instruction.data], &FeatureSet::default()).is_ok());
+        // only instructions holding a single signature are read
+        assert_eq!(
+            check(&instruction, &pass_key.pubkey(), &message),
+            Err(CashError::ClaimSignatureNotFound.into())
+        );
+    }
+
+    #[test]
+    fn test_claim_message_binds_total_claims() {
+        let (cash_link, wallet) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert_ne!(
+            claim_message(&cash_link, &wallet, 0, 1_000),
+            claim_message(&cash_link, &wallet, 1, 1_000)
+        );
+    }
+}
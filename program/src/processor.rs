@@ -3,7 +3,12 @@ use crate::instruction::CashInstruction;
 
 use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, msg, pubkey::Pubkey};
 
+pub mod batch;
+pub mod bundle;
 pub mod cashlink;
+pub mod config;
+pub mod distribute;
+pub mod stake;
 
 
 pub struct Processor;
@@ -20,10 +25,12 @@ impl Processor {
         match instruction {
             CashInstruction::InitCashLink(args) => {
                 msg!("Instruction: InitCashLink");
+                let accounts = config::assert_not_paused(accounts, program_id)?;
                 cashlink::process_init_cash_link(accounts, args, program_id)
             }
             CashInstruction::Redeem(args) => {
                 msg!("Instruction: Redeem CashLink");
+                let accounts = config::assert_not_paused(accounts, program_id)?;
                 cashlink::process_redemption(accounts, args, program_id)
             }
             CashInstruction::Cancel(args) => {
@@ -34,6 +41,32 @@ impl Processor {
                 msg!("Instruction: Close");
                 cashlink::process_close(accounts,  program_id)
             }
+            CashInstruction::BatchInitCashLinks(args) => {
+                msg!("Instruction: BatchInitCashLinks");
+                let accounts = config::assert_not_paused(accounts, program_id)?;
+                batch::process_batch_init_cash_links(accounts, args, program_id)
+            }
+            CashInstruction::Distribute(args) => {
+                msg!("Instruction: Distribute");
+                let accounts = config::assert_not_paused(accounts, program_id)?;
+                distribute::process_distribute(accounts, args, program_id)
+            }
+            CashInstruction::Refund(args) => {
+                msg!("Instruction: Refund");
+                cashlink::process_refund(accounts, program_id, args)
+            }
+            CashInstruction::SetConfig(args) => {
+                msg!("Instruction: SetConfig");
+                config::process_set_config(accounts, args, program_id)
+            }
+            CashInstruction::SetFrozen(args) => {
+                msg!("Instruction: SetFrozen");
+                cashlink::process_set_frozen(accounts, program_id, args)
+            }
+            CashInstruction::MigrateCashLink => {
+                msg!("Instruction: MigrateCashLink");
+                cashlink::process_migrate_cash_link(accounts, program_id)
+            }
         }
     }
 }
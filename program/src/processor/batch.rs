@@ -0,0 +1,172 @@
+//! Batch creation of identical cash links
+//!
+//! Every link gets its own PDA and vault. The owner makes a single deposit
+//! for the whole batch into the first link, which then funds every other
+//! link out of it.
+use crate::{
+    error::CashError,
+    instruction::BatchInitCashLinksArgs,
+    math::SafeMath,
+    processor::cashlink::{
+        assert_owner_token, assert_refund_token, create_cash_link, create_vault, deposit_split,
+        populate_cash_link,
+    },
+    quote::deposit_quote,
+    state::cashlink::CashLink,
+    utils::{assert_signer, move_lamports, native_transfer, spl_token_transfer},
+};
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::{clock::Clock, Sysvar},
+};
+
+/// Accounts preceding the mint and the links
+const BATCH_HEADER_ACCOUNTS: usize = 8;
+
+pub fn process_batch_init_cash_links(
+    accounts: &[AccountInfo],
+    args: BatchInitCashLinksArgs,
+    program_id: &Pubkey,
+) -> ProgramResult {
+    let BatchInitCashLinksArgs { args, cash_link_bumps } = args;
+    if cash_link_bumps.is_empty()
+        || args.hashlock.is_some()
+        || args.bundle.is_some()
+        || args.nft.unwrap_or_default()
+        || args.stake.is_some()
+    {
+        return Err(CashError::InvalidBatch.into());
+    }
+    let num_links = cash_link_bumps.len();
+    let num_link_accounts = accounts
+        .len()
+        .checked_sub(BATCH_HEADER_ACCOUNTS)
+        .ok_or(ProgramError::NotEnoughAccountKeys)?;
+    // a token batch refunding another wallet than the owner passes its token
+    // account too
+    let num_token_accounts = if args.refund_to.is_some() { 3 } else { 2 };
+    let has_mint = if num_link_accounts == 2 * num_links {
+        false
+    } else if num_link_accounts == num_token_accounts + 3 * num_links {
+        true
+    } else {
+        return Err(CashError::InvalidBatch.into());
+    };
+
+    let account_info_iter = &mut accounts.iter();
+    let authority_info = next_account_info(account_info_iter)?;
+    assert_signer(authority_info)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let fee_payer_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+    let clock_info = next_account_info(account_info_iter)?;
+    let _token_program_info = next_account_info(account_info_iter)?;
+    let _associated_token_program_info = next_account_info(account_info_iter)?;
+    let clock = &Clock::from_account_info(clock_info)?;
+
+    let (mint_info, owner_token_info) = if has_mint {
+        let mint_info = next_account_info(account_info_iter)?;
+        let owner_token_info = next_account_info(account_info_iter)?;
+        if args.refund_to.is_some() {
+            let _refund_token_info = next_account_info(account_info_iter)?;
+            assert_refund_token(accounts, args.refund_to.as_ref(), mint_info.key)?;
+        }
+        (Some(mint_info), Some(owner_token_info))
+    } else {
+        (None, None)
+    };
+
+    // every link is a copy of the same validated template but for its pass key
+    let mut template = CashLink::default();
+    populate_cash_link(
+        &mut template,
+        &args,
+        authority_info.key,
+        owner_info.key,
+        &Pubkey::default(),
+        mint_info,
+        clock.unix_timestamp as u64,
+    )?;
+    let (token_deposit, lamport_deposit) = deposit_split(&template, &deposit_quote(&args)?)?;
+    if let Some(owner_token_info) = owner_token_info {
+        let owner_token = assert_owner_token(owner_token_info, owner_info.key)?;
+        if owner_token.amount < token_deposit.error_mul(num_links as u64)? {
+            return Err(CashError::InsufficientSettlementFunds.into());
+        }
+    }
+
+    msg!("Initialize {} cash links", num_links);
+    let mut links = Vec::with_capacity(num_links);
+    for bump in cash_link_bumps {
+        let cash_link_info = next_account_info(account_info_iter)?;
+        let pass_info = next_account_info(account_info_iter)?;
+        create_cash_link(
+            program_id,
+            cash_link_info,
+            fee_payer_info,
+            rent_info,
+            system_account_info,
+            &[CashLink::PREFIX.as_bytes(), pass_info.key.as_ref(), &[bump]],
+        )?;
+        let vault_token_info = match mint_info {
+            Some(mint_info) => {
+                let vault_token_info = next_account_info(account_info_iter)?;
+                create_vault(
+                    cash_link_info,
+                    vault_token_info,
+                    mint_info,
+                    fee_payer_info,
+                    rent_info,
+                )?;
+                Some(vault_token_info)
+            }
+            None => None,
+        };
+        let cash_link = CashLink {
+            pass_key: *pass_info.key,
+            ..template.clone()
+        };
+        CashLink::pack(cash_link, &mut cash_link_info.data.borrow_mut())?;
+        links.push((cash_link_info, pass_info, bump, vault_token_info));
+    }
+
+    // the owner funds the first link with the deposit of the whole batch and
+    // the first link funds the others
+    let (funding_info, funding_pass_info, funding_bump, funding_vault_info) = links[0];
+    if let (Some(owner_token_info), Some(funding_vault_info)) =
+        (owner_token_info, funding_vault_info)
+    {
+        let total = token_deposit.error_mul(num_links as u64)?;
+        spl_token_transfer(owner_token_info, funding_vault_info, owner_info, total, &[])?;
+        let signer_seeds = [
+            CashLink::PREFIX.as_bytes(),
+            funding_pass_info.key.as_ref(),
+            &[funding_bump],
+        ];
+        for (_, _, _, vault_token_info) in &links[1..] {
+            let vault_token_info = vault_token_info.ok_or(CashError::InvalidBatch)?;
+            spl_token_transfer(
+                funding_vault_info,
+                vault_token_info,
+                funding_info,
+                token_deposit,
+                &[&signer_seeds],
+            )?;
+        }
+    }
+    if lamport_deposit > 0 {
+        let total = lamport_deposit.error_mul(num_links as u64)?;
+        native_transfer(owner_info, funding_info, total, &[])?;
+        for (cash_link_info, ..) in &links[1..] {
+            move_lamports(funding_info, cash_link_info, lamport_deposit)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,260 @@
+//! Bundle assets escrowed alongside the main asset of a cash link
+//!
+//! Bundle accounts trail the instruction accounts as one
+//! `(mint, vault, counterparty)` triple per asset, in `CashLink::bundle` order.
+//! The counterparty is the owner's token account on init and cancel and the
+//! recipient's token account on redemption. Native assets are held by the cash
+//! link account itself, so their vault is the cash link and their counterparty
+//! the owner or redemption recipient. What a redemption leaving the link fully
+//! redeemed does not pay out goes to the refund wallet and its associated token
+//! accounts, found by key among the instruction accounts.
+use crate::{
+    error::CashError,
+    instruction::BundleAssetArgs,
+    math::SafeMath,
+    quote::bundle_share,
+    state::cashlink::{BundleAsset, CashLink, MAX_BUNDLE_ASSETS},
+    utils::{
+        assert_account_key, assert_initialized, assert_owned_by, assert_token_owned_by,
+        create_associated_token_account_raw, exists, find_account, native_transfer, spl_token_close,
+        spl_token_transfer,
+    },
+};
+
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::state::Account as TokenAccount;
+
+pub const BUNDLE_ACCOUNTS_PER_ASSET: usize = 3;
+
+/// Validate bundle arguments against the link's main `mint`
+pub fn bundle_from_args(
+    args: &[BundleAssetArgs],
+    mint: Option<&Pubkey>,
+) -> Result<Vec<BundleAsset>, CashError> {
+    if args.len() > MAX_BUNDLE_ASSETS {
+        return Err(CashError::InvalidBundle);
+    }
+    let mut bundle: Vec<BundleAsset> = Vec::with_capacity(args.len());
+    for asset in args {
+        let asset = BundleAsset {
+            mint: asset.mint,
+            amount: asset.amount,
+            remaining_amount: asset.amount,
+        };
+        if asset.amount == 0
+            || Some(&asset.mint) == mint
+            || (asset.is_native() && mint.is_none())
+            || bundle.iter().any(|other| other.mint == asset.mint)
+        {
+            return Err(CashError::InvalidBundle);
+        }
+        bundle.push(asset);
+    }
+    Ok(bundle)
+}
+
+/// The trailing accounts of an instruction for `num_assets` bundle assets
+fn bundle_accounts<'a, 'b>(
+    accounts: &'b [AccountInfo<'a>],
+    num_assets: usize,
+) -> Result<&'b [AccountInfo<'a>], ProgramError> {
+    let len = num_assets * BUNDLE_ACCOUNTS_PER_ASSET;
+    if accounts.len() < len {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    Ok(&accounts[accounts.len() - len..])
+}
+
+fn assert_vault<'a, 'b>(
+    asset: &BundleAsset,
+    cash_link_info: &AccountInfo<'a>,
+    infos: &'b [AccountInfo<'a>],
+) -> Result<(&'b AccountInfo<'a>, &'b AccountInfo<'a>, &'b AccountInfo<'a>), ProgramError> {
+    let (mint_info, vault_info, counterparty_info) = (&infos[0], &infos[1], &infos[2]);
+    assert_account_key(mint_info, &asset.mint, Some(CashError::InvalidMint))?;
+    if asset.is_native() {
+        assert_account_key(vault_info, cash_link_info.key, Some(CashError::InvalidVaultToken))?;
+    } else {
+        assert_account_key(
+            vault_info,
+            &get_associated_token_address(cash_link_info.key, &asset.mint),
+            Some(CashError::InvalidVaultTokenOwner),
+        )?;
+    }
+    Ok((mint_info, vault_info, counterparty_info))
+}
+
+/// Escrow every bundle asset from the owner. Native assets of a `prefunded`
+/// link are already held by the cash link account
+#[allow(clippy::too_many_arguments)]
+pub fn deposit_bundle<'a>(
+    cash_link: &CashLink,
+    cash_link_info: &AccountInfo<'a>,
+    owner_info: &AccountInfo<'a>,
+    fee_payer_info: &AccountInfo<'a>,
+    rent_info: &AccountInfo<'a>,
+    accounts: &[AccountInfo<'a>],
+    prefunded: bool,
+) -> ProgramResult {
+    let bundle_infos = bundle_accounts(accounts, cash_link.bundle.len())?;
+    for (asset, infos) in cash_link
+        .bundle
+        .iter()
+        .zip(bundle_infos.chunks(BUNDLE_ACCOUNTS_PER_ASSET))
+    {
+        let (mint_info, vault_info, owner_token_info) = assert_vault(asset, cash_link_info, infos)?;
+        if asset.is_native() {
+            assert_account_key(owner_token_info, owner_info.key, Some(CashError::InvalidOwner))?;
+            if !prefunded {
+                native_transfer(owner_info, cash_link_info, asset.amount, &[])?;
+            }
+            continue;
+        }
+        if !exists(vault_info)? {
+            msg!("Create a bundle vault for {}", asset.mint);
+            create_associated_token_account_raw(
+                fee_payer_info,
+                vault_info,
+                cash_link_info,
+                mint_info,
+                rent_info,
+            )?;
+        }
+        assert_owned_by(owner_token_info, &spl_token::id())?;
+        let owner_token: TokenAccount = assert_initialized(owner_token_info)?;
+        assert_token_owned_by(&owner_token, owner_info.key)?;
+        spl_token_transfer(owner_token_info, vault_info, owner_info, asset.amount, &[])?;
+    }
+    Ok(())
+}
+
+/// Pay the recipient of a redemption its share of every bundle asset. The
+/// `last` redemption refunds what is left of them. Native shares are pushed to
+/// `lamport_moves`, to move once every token transfer has been invoked
+#[allow(clippy::too_many_arguments)]
+pub fn redeem_bundle<'a, 'b>(
+    cash_link: &mut CashLink,
+    cash_link_info: &AccountInfo<'a>,
+    recipient_info: &AccountInfo<'a>,
+    fee_payer_info: &AccountInfo<'a>,
+    rent_info: &AccountInfo<'a>,
+    accounts: &'b [AccountInfo<'a>],
+    amount_to_redeem: u64,
+    remaining_amount: u64,
+    last: bool,
+    signer_seeds: &[&[u8]],
+    lamport_moves: &mut Vec<(&'b AccountInfo<'a>, u64)>,
+) -> ProgramResult {
+    let bundle_infos = bundle_accounts(accounts, cash_link.bundle.len())?;
+    let refund_to = *cash_link.refund_address();
+    for (asset, infos) in cash_link
+        .bundle
+        .iter_mut()
+        .zip(bundle_infos.chunks(BUNDLE_ACCOUNTS_PER_ASSET))
+    {
+        let (mint_info, vault_info, recipient_token_info) = assert_vault(asset, cash_link_info, infos)?;
+        let share = bundle_share(asset.remaining_amount, amount_to_redeem, remaining_amount)?;
+        asset.remaining_amount = asset.remaining_amount.error_sub(share)?;
+        let leftover = if last { asset.remaining_amount } else { 0 };
+        let refund_info = if leftover > 0 {
+            Some(find_account(accounts, &refund_to).ok_or(CashError::InvalidOwner)?)
+        } else {
+            None
+        };
+        if asset.is_native() {
+            assert_account_key(recipient_token_info, recipient_info.key, Some(CashError::InvalidDstTokenOwner))?;
+            lamport_moves.push((recipient_token_info, share));
+            if let Some(refund_info) = refund_info {
+                lamport_moves.push((refund_info, leftover));
+                asset.remaining_amount = 0;
+            }
+            continue;
+        }
+        if exists(recipient_token_info)? {
+            assert_owned_by(recipient_token_info, &spl_token::id())?;
+            let recipient_token: TokenAccount = assert_initialized(recipient_token_info)?;
+            assert_token_owned_by(&recipient_token, recipient_info.key)?;
+        } else {
+            create_associated_token_account_raw(
+                fee_payer_info,
+                recipient_token_info,
+                recipient_info,
+                mint_info,
+                rent_info,
+            )?;
+        }
+        if share > 0 {
+            spl_token_transfer(vault_info, recipient_token_info, cash_link_info, share, &[signer_seeds])?;
+        }
+        if let Some(refund_info) = refund_info {
+            let refund_token_info = find_account(
+                accounts,
+                &get_associated_token_address(&refund_to, &asset.mint),
+            )
+            .ok_or(CashError::InvalidOwner)?;
+            if exists(refund_token_info)? {
+                let refund_token: TokenAccount = assert_initialized(refund_token_info)?;
+                assert_token_owned_by(&refund_token, &refund_to)?;
+            } else {
+                create_associated_token_account_raw(
+                    fee_payer_info,
+                    refund_token_info,
+                    refund_info,
+                    mint_info,
+                    rent_info,
+                )?;
+            }
+            spl_token_transfer(vault_info, refund_token_info, cash_link_info, leftover, &[signer_seeds])?;
+            asset.remaining_amount = 0;
+        }
+        if last {
+            spl_token_close(vault_info, fee_payer_info, cash_link_info, &[signer_seeds])?;
+        }
+    }
+    Ok(())
+}
+
+/// Refund what is left of every bundle asset to `refund_to`. Native assets are
+/// pushed to `lamport_moves`, to move once every token transfer has been invoked
+pub fn refund_bundle<'a, 'b>(
+    cash_link: &mut CashLink,
+    cash_link_info: &AccountInfo<'a>,
+    fee_payer_info: &AccountInfo<'a>,
+    accounts: &'b [AccountInfo<'a>],
+    refund_to: &Pubkey,
+    signer_seeds: &[&[u8]],
+    lamport_moves: &mut Vec<(&'b AccountInfo<'a>, u64)>,
+) -> ProgramResult {
+    let bundle_infos = bundle_accounts(accounts, cash_link.bundle.len())?;
+    for (asset, infos) in cash_link
+        .bundle
+        .iter_mut()
+        .zip(bundle_infos.chunks(BUNDLE_ACCOUNTS_PER_ASSET))
+    {
+        let (_, vault_info, refund_info) = assert_vault(asset, cash_link_info, infos)?;
+        if asset.is_native() {
+            assert_account_key(refund_info, refund_to, Some(CashError::InvalidOwner))?;
+            lamport_moves.push((refund_info, asset.remaining_amount));
+        } else {
+            let vault_token: TokenAccount = assert_initialized(vault_info)?;
+            if vault_token.amount > 0 {
+                let refund_token: TokenAccount = assert_initialized(refund_info)?;
+                assert_token_owned_by(&refund_token, refund_to)?;
+                spl_token_transfer(
+                    vault_info,
+                    refund_info,
+                    cash_link_info,
+                    vault_token.amount,
+                    &[signer_seeds],
+                )?;
+            }
+            spl_token_close(vault_info, fee_payer_info, cash_link_info, &[signer_seeds])?;
+        }
+        asset.remaining_amount = 0;
+    }
+    Ok(())
+}
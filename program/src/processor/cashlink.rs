@@ -1,45 +1,71 @@
 use crate::{
+    allowlist,
+    antibot::{assert_no_denied_programs, assert_not_cpi},
+    claim::{assert_claim_signed, claim_message},
     error::CashError::{
-        self, AccountAlreadyExpired, AccountAlreadyRedeemed, AccountNotExpired,
-        AmountOverflow, InsufficientSettlementFunds,
+        self, AccountAlreadyRedeemed, AmountOverflow, InsufficientSettlementFunds,
+    },
+    find_cash_link_redemption_program_address, find_fingerprint_program_address,
+    instruction::{
+        CancelCashRedemptionArgs, InitCashLinkArgs, InitCashRedemptionArgs, RefundArgs,
+        SetFrozenArgs,
     },
-    instruction::{CancelCashRedemptionArgs, InitCashLinkArgs, InitCashRedemptionArgs},
     math::SafeMath,
+    processor::{
+        bundle::{
+            bundle_from_args, deposit_bundle, redeem_bundle, refund_bundle,
+            BUNDLE_ACCOUNTS_PER_ASSET,
+        },
+        stake::{deposit_stake, release_stake},
+    },
+    quote::{deposit_quote, redemption_quote, DepositQuote},
     state::{
-        cashlink::{CashLink, CashLinkState, DistributionType}, REDEMPTION_PREFIX, AccountType, FINGERPRINT_PREFIX, FLAG_ACCOUNT_SIZE
+        cashlink::{
+            CashLink, CashLinkState, DistributionType, LEGACY_CASH_LINK_DATA_SIZE,
+            MAX_DENIED_PROGRAMS,
+        },
+        redemption::Redemption,
+        transitions::{transition, CashLinkEvent}, REDEMPTION_PREFIX, AccountType, FINGERPRINT_PREFIX, FLAG_ACCOUNT_SIZE
     },
     utils::{
-        assert_account_key, assert_initialized, assert_owned_by, assert_signer,
-        assert_token_owned_by, calculate_fee, create_associated_token_account_raw,
-        create_new_account_raw, empty_account_balance, exists, get_random_value, native_transfer,
-        spl_token_close, spl_token_transfer,
+        assert_account_key, assert_initialized, assert_migrated, assert_owned_by, assert_signer,
+        assert_token_owned_by, cmp_pubkeys, create_associated_token_account_raw,
+        create_new_account_raw, empty_account_balance, exists, find_account, get_random_value,
+        move_lamports, native_transfer, spl_token_close, spl_token_transfer,
     },
 };
 
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
+    hash::hash,
     msg,
     program_error::ProgramError,
     program_pack::Pack,
     pubkey::Pubkey,
     rent::Rent,
-    sysvar::{clock::Clock, slot_hashes, Sysvar},
+    system_program,
+    sysvar::{self, clock::Clock, slot_hashes, Sysvar},
 };
 use spl_associated_token_account::get_associated_token_address;
-use spl_token::state::Account as TokenAccount;
+use spl_token::state::{Account as TokenAccount, Mint};
 
 pub struct Processor;
 
 pub fn process_init_cash_link(
     accounts: &[AccountInfo],
-    args: InitCashLinkArgs,
+    mut args: InitCashLinkArgs,
     program_id: &Pubkey,
 ) -> ProgramResult {
+    // an NFT is gifted once
+    if args.nft.unwrap_or_default() {
+        args.max_num_redemptions = 1;
+    }
     let account_info_iter = &mut accounts.iter();
     let authority_info = next_account_info(account_info_iter)?;
     assert_signer(authority_info)?;
     let owner_info = next_account_info(account_info_iter)?;
+    assert_signer(owner_info)?;
     let fee_payer_info = next_account_info(account_info_iter)?;
     let cash_link_info = next_account_info(account_info_iter)?;
     let pass_info = next_account_info(account_info_iter)?;
@@ -51,7 +77,9 @@ pub fn process_init_cash_link(
     let clock = &Clock::from_account_info(clock_info)?;
 
     msg!("Start to read the mint info for the cashlink");
-    let mint_info = if account_info_iter.len() > 1 {
+    let num_bundle_accounts = args.bundle.as_ref().map_or(0, Vec::len) * BUNDLE_ACCOUNTS_PER_ASSET;
+    let num_stake_accounts = if args.stake.is_some() { 2 } else { 0 };
+    let mint_info = if account_info_iter.len() > 1 + num_bundle_accounts + num_stake_accounts {
         msg!("Read the mint info for the cashlink");
         Some(next_account_info(account_info_iter)?)
     } else {
@@ -70,25 +98,82 @@ pub fn process_init_cash_link(
             &[args.cash_link_bump],
         ],
     )?;
+    populate_cash_link(
+        &mut cash_link,
+        &args,
+        authority_info.key,
+        owner_info.key,
+        pass_info.key,
+        mint_info,
+        clock.unix_timestamp as u64,
+    )?;
+    let quote = deposit_quote(&args)?;
+    let (token_deposit, lamport_deposit) = deposit_split(&cash_link, &quote)?;
+    if let Some(info) = mint_info {
+        let vault_token_info = next_account_info(account_info_iter)?;
+        create_vault(cash_link_info, vault_token_info, info, fee_payer_info, rent_info)?;
+        let owner_token_info = next_account_info(account_info_iter)?;
+        assert_owner_token(owner_token_info, owner_info.key)?;
+        assert_refund_token(accounts, args.refund_to.as_ref(), info.key)?;
+        spl_token_transfer(owner_token_info, vault_token_info, owner_info, token_deposit, &[])?;
+    }
+    // An owner holding data, typically the PDA of a program creating the link
+    // through CPI, cannot be debited by the system program and moves its
+    // lamport deposit to the cash link address beforehand
+    let prefunded = !cmp_pubkeys(owner_info.owner, &system_program::id());
+    if lamport_deposit > 0 && !prefunded {
+        native_transfer(owner_info, cash_link_info, lamport_deposit, &[])?;
+    }
+    if cash_link.stake.is_some() {
+        deposit_stake(&cash_link, cash_link_info, owner_info, accounts, clock)?;
+    }
+
+    if let Some(bundle) = &args.bundle {
+        cash_link.bundle = bundle_from_args(bundle, cash_link.mint.as_ref())?;
+        deposit_bundle(
+            &cash_link,
+            cash_link_info,
+            owner_info,
+            fee_payer_info,
+            rent_info,
+            accounts,
+            prefunded,
+        )?;
+    }
+    if prefunded {
+        let required = Rent::from_account_info(rent_info)?
+            .minimum_balance(CashLink::LEN)
+            .error_add(lamport_deposit)?
+            .error_add(cash_link.native_bundle_remaining()?)?;
+        if cash_link_info.lamports() < required {
+            return Err(CashError::DepositNotPrefunded.into());
+        }
+    }
+
+    CashLink::pack(cash_link, &mut cash_link_info.data.borrow_mut())?;
+    Ok(())
+}
+
+/// Validate `args` and fill in a new cash link created at `now`
+pub fn populate_cash_link(
+    cash_link: &mut CashLink,
+    args: &InitCashLinkArgs,
+    authority: &Pubkey,
+    owner: &Pubkey,
+    pass_key: &Pubkey,
+    mint_info: Option<&AccountInfo>,
+    now: u64,
+) -> ProgramResult {
     if args.amount == 0 {
         return Err(CashError::InvalidAmount.into());
     }
     if args.max_num_redemptions == 0 {
         return Err(CashError::InvalidNumberOfRedemptions.into());
     }
-    let fee_from_bps = calculate_fee(args.amount, args.fee_bps as u64)?;
-    let total_platform_fee = fee_from_bps
-        .checked_add(args.fixed_fee)
-        .ok_or::<ProgramError>(CashError::Overflow.into())?;
-
-    let total_redemption_fee = args
-        .fee_to_redeem
-        .checked_mul(args.max_num_redemptions as u64)
-        .ok_or::<ProgramError>(CashError::Overflow.into())?;
 
     let total_amount = match args.distribution_type {
         DistributionType::Fixed => {
-            if args.amount % args.max_num_redemptions as u64 != 0 {
+            if args.amount.checked_rem(args.max_num_redemptions as u64) != Some(0) {
                 return Err(CashError::InvalidAmount.into());
             }
             args.amount
@@ -109,29 +194,54 @@ pub fn process_init_cash_link(
     if args.num_days_to_expire == 0 {
         return Err(CashError::InvalidExpiryInDays.into());
     }
-    let now = clock.unix_timestamp as u64;
-    let total = total_amount
-        .checked_add(total_platform_fee)
-        .ok_or::<ProgramError>(CashError::Overflow.into())?
-        .checked_add(total_redemption_fee)
-        .ok_or::<ProgramError>(CashError::Overflow.into())?;
     cash_link.account_type = AccountType::CashLink;
     cash_link.state = CashLinkState::Initialized;
     cash_link.amount = total_amount;
     cash_link.fee_bps = args.fee_bps;
     cash_link.fixed_fee = args.fixed_fee;
     cash_link.fee_to_redeem = args.fee_to_redeem;
+    cash_link.relayer_fee = args.relayer_fee.unwrap_or_default();
+    cash_link.refund_bounty = args.refund_bounty.unwrap_or_default();
     cash_link.remaining_amount = total_amount;
-    cash_link.authority = *authority_info.key;
-    cash_link.pass_key = *pass_info.key;
-    cash_link.owner = *owner_info.key;
-    cash_link.distribution_type = args.distribution_type;
+    cash_link.authority = *authority;
+    cash_link.pass_key = *pass_key;
+    cash_link.owner = *owner;
+    cash_link.distribution_type = args.distribution_type.clone();
     cash_link.max_num_redemptions = args.max_num_redemptions;
-    cash_link.fingerprint_enabled  = match args.fingerprint_enabled {
-        Some(enabled)  => enabled,
-        None => false,
-    };
+    cash_link.fingerprint_enabled  = args.fingerprint_enabled.unwrap_or_default();
     cash_link.expires_at = now + (args.num_days_to_expire as u64 * 86400);
+    cash_link.allowlist_root = args.allowlist_root;
+    cash_link.cooldown_seconds = args.cooldown_seconds.unwrap_or_default();
+    cash_link.wallet_cooldown_seconds = args.wallet_cooldown_seconds.unwrap_or_default();
+    cash_link.max_claims_per_wallet = match args.max_claims_per_wallet {
+        Some(0) => return Err(CashError::InvalidMaxClaimsPerWallet.into()),
+        Some(max_claims) => max_claims,
+        None => 1,
+    };
+    if let Some(hashlock) = args.hashlock {
+        if pass_key.to_bytes() != hashlock {
+            return Err(CashError::InvalidPassKey.into());
+        }
+    }
+    cash_link.nft = args.nft.unwrap_or_default();
+    if cash_link.nft {
+        let info = mint_info.ok_or(CashError::InvalidNft)?;
+        assert_owned_by(info, &spl_token::id())?;
+        let mint: Mint = assert_initialized(info)?;
+        if mint.supply != 1
+            || mint.decimals != 0
+            || args.amount != 1
+            || args.bundle.is_some()
+        {
+            return Err(CashError::InvalidNft.into());
+        }
+    }
+    cash_link.fees_in_sol =
+        mint_info.is_some() && (cash_link.nft || args.fees_in_sol.unwrap_or_default());
+    if cash_link.fees_in_sol && args.fee_bps != 0 {
+        return Err(CashError::InvalidSolFee.into());
+    }
+    cash_link.hashlock = args.hashlock;
     cash_link.min_amount = match args.min_amount {
         Some(amount) if amount > total_amount => {
             return Err(CashError::MinAmountMustBeLessThanAmount.into())
@@ -139,59 +249,112 @@ pub fn process_init_cash_link(
         Some(amount) => amount,
         None => 1,
     };
+    cash_link.forbid_cpi = args.forbid_cpi.unwrap_or_default();
+    cash_link.denied_programs = args.denied_programs.clone().unwrap_or_default();
+    if cash_link.denied_programs.len() > MAX_DENIED_PROGRAMS {
+        return Err(CashError::InvalidDeniedPrograms.into());
+    }
     if cash_link.distribution_type == DistributionType::Fixed {
         msg!("Got Fixed Distribution");
     } else {
         msg!("Got Random Distribution");
     }
-    match mint_info {
-        Some(info) => {
-            cash_link.mint = Some(*info.key);
-            let vault_token_info = next_account_info(account_info_iter)?;
-            let associated_token_account =
-                get_associated_token_address(&cash_link_info.key, &info.key);
-            // let vault_token: TokenAccount = assert_initialized(associated_token_account)?;
-            // assert_token_owned_by(&vault_token, cash_link_info.key)?;
-            assert_account_key(
-                vault_token_info,
-                &associated_token_account,
-                Some(CashError::InvalidVaultTokenOwner),
-            )?;
-            if exists(vault_token_info)? {
-                msg!("Cash link has a mint and an existing vault token. Validate the vault token");
-                let vault_token: TokenAccount = assert_initialized(vault_token_info)?;
-                assert_owned_by(vault_token_info, &spl_token::id())?;
-                assert_token_owned_by(&vault_token, cash_link_info.key)?;
-                assert_account_key(info, &vault_token.mint, Some(CashError::InvalidMint))?;
-            } else {
-                msg!("Cash link has a mint. Create an associated token account for the value");
-                create_associated_token_account_raw(
-                    fee_payer_info,
-                    vault_token_info,
-                    cash_link_info,
-                    info,
-                    rent_info,
-                )?;
-            }
-            let owner_token_info = next_account_info(account_info_iter)?;
-            assert_owned_by(owner_token_info, &spl_token::id())?;
-            let owner_token: TokenAccount = assert_initialized(owner_token_info)?;
-            assert_token_owned_by(&owner_token, owner_info.key)?;
-            spl_token_transfer(owner_token_info, vault_token_info, owner_info, total, &[])?;
-            //spl_token_transfer(owner_token_info, fee_token_info, owner_info, total_platform_fee, &[])?;
+    cash_link.mint = mint_info.map(|info| *info.key);
+    if args.stake.is_some()
+        && (cash_link.mint.is_some()
+            || args.max_num_redemptions != 1
+            || args.fee_bps != 0
+            || args.bundle.is_some())
+    {
+        return Err(CashError::InvalidStake.into());
+    }
+    cash_link.stake = args.stake;
+    cash_link.refund_to = args.refund_to;
+    Ok(())
+}
+
+/// Split a deposit into the tokens moved to the vault and the lamports moved
+/// to the cash link account. The amount of a stake link stays in its stake
+/// account
+pub fn deposit_split(
+    cash_link: &CashLink,
+    quote: &DepositQuote,
+) -> Result<(u64, u64), ProgramError> {
+    let (token_deposit, lamport_deposit) =
+        quote.split(cash_link.mint.is_none(), cash_link.fees_in_sol)?;
+    if cash_link.stake.is_some() {
+        return Ok((token_deposit, lamport_deposit.error_sub(quote.amount)?));
+    }
+    Ok((token_deposit, lamport_deposit))
+}
+
+/// Validate the vault of a cash link, creating it if it does not exist yet
+pub fn create_vault<'a>(
+    cash_link_info: &AccountInfo<'a>,
+    vault_token_info: &AccountInfo<'a>,
+    mint_info: &AccountInfo<'a>,
+    fee_payer_info: &AccountInfo<'a>,
+    rent_info: &AccountInfo<'a>,
+) -> ProgramResult {
+    let associated_token_account = get_associated_token_address(cash_link_info.key, mint_info.key);
+    assert_account_key(
+        vault_token_info,
+        &associated_token_account,
+        Some(CashError::InvalidVaultTokenOwner),
+    )?;
+    if exists(vault_token_info)? {
+        msg!("Cash link has a mint and an existing vault token. Validate the vault token");
+        let vault_token: TokenAccount = assert_initialized(vault_token_info)?;
+        assert_owned_by(vault_token_info, &spl_token::id())?;
+        assert_token_owned_by(&vault_token, cash_link_info.key)?;
+        assert_account_key(mint_info, &vault_token.mint, Some(CashError::InvalidMint))?;
+    } else {
+        msg!("Cash link has a mint. Create an associated token account for the value");
+        create_associated_token_account_raw(
+            fee_payer_info,
+            vault_token_info,
+            cash_link_info,
+            mint_info,
+            rent_info,
+        )?;
+    }
+    Ok(())
+}
+
+/// Assert `owner_token_info` is a token account of `owner`
+pub fn assert_owner_token(
+    owner_token_info: &AccountInfo,
+    owner: &Pubkey,
+) -> Result<TokenAccount, ProgramError> {
+    assert_owned_by(owner_token_info, &spl_token::id())?;
+    let owner_token: TokenAccount = assert_initialized(owner_token_info)?;
+    assert_token_owned_by(&owner_token, owner)?;
+    Ok(owner_token)
+}
+
+/// Assert the refund wallet of a token link has a token account for `mint`,
+/// passed among `accounts`, to take what the last redemption leaves
+pub fn assert_refund_token(
+    accounts: &[AccountInfo],
+    refund_to: Option<&Pubkey>,
+    mint: &Pubkey,
+) -> ProgramResult {
+    if let Some(refund_to) = refund_to {
+        let refund_token_info =
+            find_account(accounts, &get_associated_token_address(refund_to, mint))
+                .ok_or(CashError::InvalidRefundToken)?;
+        if refund_token_info.owner != &spl_token::id() {
+            return Err(CashError::InvalidRefundToken.into());
         }
-        None => {
-            native_transfer(owner_info, cash_link_info, total, &[])?;
-            //native_transfer(owner_info, fee_token_info, total_platform_fee, &[])?;
-            cash_link.mint = None;
+        let refund_token = assert_owner_token(refund_token_info, refund_to)?;
+        if refund_token.mint != *mint {
+            return Err(CashError::InvalidRefundToken.into());
         }
-    };
-
-    CashLink::pack(cash_link, &mut cash_link_info.data.borrow_mut())?;
+    }
     Ok(())
 }
 
-fn create_cash_link<'a>(
+pub fn create_cash_link<'a>(
     program_id: &Pubkey,
     cash_link_info: &AccountInfo<'a>,
     owner_info: &AccountInfo<'a>,
@@ -239,6 +402,7 @@ pub fn process_cancel(
 
     let cash_link_info = next_account_info(account_info_iter)?;
     assert_owned_by(cash_link_info, program_id)?;
+    assert_migrated(cash_link_info)?;
     let pass_info = next_account_info(account_info_iter)?;
     let mut cash_link = CashLink::unpack(&cash_link_info.data.borrow())?;
 
@@ -261,12 +425,7 @@ pub fn process_cancel(
     let clock = &Clock::from_account_info(clock_info)?;
     let rent_info = next_account_info(account_info_iter)?;
 
-    if cash_link.expired() {
-        return Err(AccountAlreadyExpired.into());
-    }
-    if cash_link.redeemed() {
-        return Err(AccountAlreadyRedeemed.into());
-    }
+    let state = transition(&cash_link.state, CashLinkEvent::Cancel)?;
 
     if (clock.unix_timestamp as u64) <= cash_link.expires_at {
         return Err(CashError::CashlinkNotExpired.into());
@@ -278,11 +437,52 @@ pub fn process_cancel(
         &[args.cash_link_bump],
     ];
 
-    if let Some(mint) = cash_link.mint {
-        let vault_token_info = next_account_info(account_info_iter)?;
+    let vault_token_info = match cash_link.mint {
+        Some(_) => Some(next_account_info(account_info_iter)?),
+        None => None,
+    };
+    let rent = &Rent::from_account_info(rent_info)?;
+    if cash_link.stake.is_some() {
+        release_stake(&cash_link, cash_link_info, accounts, cash_link.refund_address(), &signer_seeds)?;
+    }
+    refund_owner(
+        &mut cash_link,
+        cash_link_info,
+        owner_token_info,
+        vault_token_info,
+        fee_payer_info,
+        rent,
+        accounts,
+        &signer_seeds,
+        Vec::new(),
+    )?;
+    msg!("Mark the cash_link account as cancelled...");
+    cash_link.state = state;
+    cash_link.cancel_reason = args.reason.unwrap_or_default();
+    cash_link.ended_at = Some(clock.unix_timestamp as u64);
+    CashLink::pack(cash_link, &mut cash_link_info.data.borrow_mut())?;
+    Ok(())
+}
+
+/// Return everything left on a cash link to its refund address. Closed vaults
+/// send their rent to `close_to_info`. `lamport_moves` are paid out of the link
+/// before the refund, once every token account is settled
+#[allow(clippy::too_many_arguments)]
+fn refund_owner<'a, 'b>(
+    cash_link: &mut CashLink,
+    cash_link_info: &AccountInfo<'a>,
+    owner_token_info: &'b AccountInfo<'a>,
+    vault_token_info: Option<&AccountInfo<'a>>,
+    close_to_info: &AccountInfo<'a>,
+    rent: &Rent,
+    accounts: &'b [AccountInfo<'a>],
+    signer_seeds: &[&[u8]],
+    mut lamport_moves: Vec<(&'b AccountInfo<'a>, u64)>,
+) -> ProgramResult {
+    if let (Some(mint), Some(vault_token_info)) = (cash_link.mint, vault_token_info) {
         let vault_token: TokenAccount = assert_initialized(vault_token_info)?;
         // assert_account_key(vault_token.mint, mint, Some(CashError::InvalidMint))?;
-        let associated_token_account = get_associated_token_address(&cash_link_info.key, &mint);
+        let associated_token_account = get_associated_token_address(cash_link_info.key, &mint);
         assert_account_key(
             vault_token_info,
             &associated_token_account,
@@ -290,48 +490,59 @@ pub fn process_cancel(
         )?;
         if vault_token.amount > 0 {
             let owner_token: TokenAccount = assert_initialized(owner_token_info)?;
-            assert_token_owned_by(&owner_token, &cash_link.owner)?;
+            assert_token_owned_by(&owner_token, cash_link.refund_address())?;
             spl_token_transfer(
                 vault_token_info,
                 owner_token_info,
                 cash_link_info,
                 vault_token.amount,
-                &[&signer_seeds],
+                &[signer_seeds],
             )?;
             spl_token_close(
                 vault_token_info,
-                fee_payer_info,
+                close_to_info,
                 cash_link_info,
-                &[&signer_seeds],
+                &[signer_seeds],
             )?;
         } else {
             spl_token_close(
                 vault_token_info,
-                fee_payer_info,
+                close_to_info,
                 cash_link_info,
-                &[&signer_seeds],
+                &[signer_seeds],
             )?;
         }
+    } else if cash_link.mint.is_none() {
+        assert_account_key(owner_token_info, cash_link.refund_address(), Some(CashError::InvalidOwner))?;
     } else {
-        let rent = &Rent::from_account_info(rent_info)?;
-        let min_lamports = rent.minimum_balance(CashLink::LEN);
-        let source_starting_lamports = cash_link_info.lamports();
-        let remaining_amount = source_starting_lamports
-            .checked_sub(min_lamports)
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    let refund_to = *cash_link.refund_address();
+    refund_bundle(
+        cash_link,
+        cash_link_info,
+        close_to_info,
+        accounts,
+        &refund_to,
+        signer_seeds,
+        &mut lamport_moves,
+    )?;
+    // lamports move directly only once every token account is settled
+    for (info, amount) in lamport_moves {
+        move_lamports(cash_link_info, info, amount)?;
+    }
+    if cash_link.mint.is_none() {
+        let remaining_amount = cash_link_info
+            .lamports()
+            .checked_sub(rent.minimum_balance(CashLink::LEN))
             .ok_or(AmountOverflow)?;
         if remaining_amount > 0 {
-            **cash_link_info.lamports.borrow_mut() = min_lamports;
-
-            let dest_starting_lamports = owner_token_info.lamports();
-            **owner_token_info.lamports.borrow_mut() = dest_starting_lamports
-                .checked_add(remaining_amount)
-                .ok_or(AmountOverflow)?;
+            move_lamports(cash_link_info, owner_token_info, remaining_amount)?;
         }
+    } else if cash_link.has_fee_deposit() {
+        refund_fee_deposit(cash_link, cash_link_info, accounts, rent)?;
     }
-
-    msg!("Mark the cash_link account as expired...");
-    cash_link.state = CashLinkState::Expired;
-    CashLink::pack(cash_link, &mut cash_link_info.data.borrow_mut())?;
     Ok(())
 }
 
@@ -348,10 +559,17 @@ pub fn process_redemption(
     assert_signer(authority_info)?;
 
     let wallet_info = next_account_info(account_info_iter)?;
+    assert_signer(wallet_info)?;
+    // funds go to the recipient while claims are counted against the wallet
+    let recipient_info = match &args.recipient {
+        Some(recipient) => find_account(accounts, recipient).ok_or(CashError::RecipientNotFound)?,
+        None => wallet_info,
+    };
     let fee_token_info = next_account_info(account_info_iter)?;
     let cash_link_info = next_account_info(account_info_iter)?;
     let pass_info = next_account_info(account_info_iter)?;
     assert_owned_by(cash_link_info, program_id)?;
+    assert_migrated(cash_link_info)?;
     let mut cash_link = CashLink::unpack(&cash_link_info.data.borrow())?;
 
     assert_account_key(
@@ -366,19 +584,36 @@ pub fn process_redemption(
         Some(CashError::InvalidPassKey),
     )?;
 
-    assert_signer(pass_info)?;
+    if let Some(hashlock) = cash_link.hashlock {
+        let secret = args.secret.as_ref().ok_or(CashError::SecretNotFound)?;
+        if hash(secret.as_bytes()).to_bytes() != hashlock {
+            return Err(CashError::InvalidSecret.into());
+        }
+    } else if args.claim_expires_at.is_none() {
+        assert_signer(pass_info)?;
+    }
 
-    if cash_link.expired() {
-        return Err(AccountAlreadyExpired.into());
+    if cash_link.forbid_cpi {
+        assert_not_cpi()?;
     }
-    if cash_link.redeemed() {
-        return Err(AccountAlreadyRedeemed.into());
+    if !cash_link.denied_programs.is_empty() {
+        let instructions_info = find_account(accounts, &sysvar::instructions::id())
+            .ok_or(CashError::InstructionsSysvarNotFound)?;
+        assert_no_denied_programs(instructions_info, &cash_link.denied_programs)?;
     }
 
+    cash_link.state = transition(&cash_link.state, CashLinkEvent::Redeem)?;
+
     let redemption_info = next_account_info(account_info_iter)?;
-    if redemption_info.lamports() > 0 && !redemption_info.data_is_empty() {
-        msg!("AccountAlreadyInitialized");
-        return Err(ProgramError::AccountAlreadyInitialized);
+    let mut redemption = load_redemption(
+        redemption_info,
+        cash_link_info,
+        wallet_info.key,
+        args.redemption_bump,
+        program_id,
+    )?;
+    if redemption.total_claims >= cash_link.max_claims_per_wallet {
+        return Err(CashError::MaxClaimsPerWalletReached.into());
     }
     let owner_token_info = next_account_info(account_info_iter)?; //owner_token_info
     let fee_payer_info = next_account_info(account_info_iter)?;
@@ -391,10 +626,40 @@ pub fn process_redemption(
         return Err(CashError::CashlinkExpired.into());
     }
 
+    let now = clock.unix_timestamp as u64;
+    if let Some(last_redeemed_at) = cash_link.last_redeemed_at {
+        if now < last_redeemed_at.saturating_add(cash_link.cooldown_seconds) {
+            return Err(CashError::CooldownActive.into());
+        }
+    }
+    if redemption.total_claims > 0
+        && now < redemption.redeemed_at.saturating_add(cash_link.wallet_cooldown_seconds)
+    {
+        return Err(CashError::WalletCooldownActive.into());
+    }
+
+    if let (None, Some(claim_expires_at)) = (cash_link.hashlock, args.claim_expires_at) {
+        if clock.unix_timestamp as u64 > claim_expires_at {
+            return Err(CashError::ClaimExpired.into());
+        }
+        let instructions_info = find_account(accounts, &sysvar::instructions::id())
+            .ok_or(CashError::InstructionsSysvarNotFound)?;
+        assert_claim_signed(
+            instructions_info,
+            &cash_link.pass_key,
+            &claim_message(
+                cash_link_info.key,
+                wallet_info.key,
+                redemption.total_claims,
+                claim_expires_at,
+            ),
+        )?;
+    }
+
     assert_account_key(
         recent_slothashes_info,
         &slot_hashes::id(),
-        Some(CashError::InvalidSlotHashProgram.into()),
+        Some(CashError::InvalidSlotHashProgram),
     )?;
 
     let signer_seeds = [
@@ -403,36 +668,251 @@ pub fn process_redemption(
         &[args.cash_link_bump],
     ];
 
+    if let Some(root) = cash_link.allowlist_root {
+        let proof = args
+            .allowlist_proof
+            .as_ref()
+            .ok_or(CashError::AllowlistProofNotFound)?;
+        if !allowlist::verify(proof, &root, allowlist::leaf(wallet_info.key, args.allowlist_amount)) {
+            return Err(CashError::WalletNotAllowlisted.into());
+        }
+    }
+
+    let allowlisted_amount = cash_link.allowlist_root.and(args.allowlist_amount);
+    // an allowlisted amount is the wallet's whole share, claimed at once
+    if allowlisted_amount.is_some() && redemption.total_claims > 0 {
+        return Err(CashError::MaxClaimsPerWalletReached.into());
+    }
+    let amount_to_redeem = redemption_amount(&cash_link, allowlisted_amount, || {
+        get_random_value(recent_slothashes_info, clock)
+    })?;
+    let token = match cash_link.mint {
+        Some(_) => Some((
+            next_account_info(account_info_iter)?,
+            next_account_info(account_info_iter)?,
+            next_account_info(account_info_iter)?,
+        )),
+        None => None,
+    };
+    let system_account_info = next_account_info(account_info_iter)?;
+    let first_claim = redemption.total_claims == 0;
+    if first_claim {
+        create_new_account_raw(
+            program_id,
+            redemption_info,
+            rent_info,
+            fee_payer_info,
+            system_account_info,
+            Redemption::LEN,
+            &[
+                REDEMPTION_PREFIX.as_bytes(),
+                cash_link_info.key.as_ref(),
+                wallet_info.key.as_ref(),
+                &[args.redemption_bump],
+            ],
+        )?;
+    }
+    // a wallet proves its fingerprint once, on its first claim
+    if cash_link.fingerprint_enabled && first_claim {
+        if let Some(bump) = args.fingerprint_bump {
+            if let Some(fingerprint) = args.fingerprint {
+                let fingerprint_account_info = next_account_info(account_info_iter)?;
+                let fingerprint = bs58::decode(fingerprint)
+                    .into_vec()
+                    .map_err(|_| CashError::InvalidFingerprint)?;
+                let (fingerprint_key, fingerprint_bump) =
+                    find_fingerprint_program_address(program_id, cash_link_info.key, &fingerprint);
+                if *fingerprint_account_info.key != fingerprint_key || bump != fingerprint_bump {
+                    return Err(CashError::InvalidFingerprint.into());
+                }
+                if fingerprint_account_info.lamports() > 0
+                    && !fingerprint_account_info.data_is_empty()
+                {
+                    msg!("Fingerprint AccountAlreadyInitialized");
+                    return Err(ProgramError::AccountAlreadyInitialized);
+                }
+                create_new_account_raw(
+                    program_id,
+                    fingerprint_account_info,
+                    rent_info,
+                    fee_payer_info,
+                    system_account_info,
+                    FLAG_ACCOUNT_SIZE,
+                    &[
+                        FINGERPRINT_PREFIX.as_bytes(),
+                        cash_link_info.key.as_ref(),
+                        &fingerprint,
+                        &[bump],
+                    ],
+                )?;
+            } else {
+                return Err(CashError::FingerprintFound.into());
+            }
+        } else {
+            return Err(CashError::FingerprintBumpNotFound.into());
+        }
+        if args.fingerprint_bump.is_none() {
+            return Err(CashError::FingerprintBumpNotFound.into());
+        }
+    }
+    let mut lamport_moves = Vec::new();
+    pay_out(
+        &mut cash_link,
+        &PayoutAccounts {
+            cash_link_info,
+            recipient_info,
+            fee_token_info,
+            owner_token_info,
+            fee_payer_info,
+            rent_info,
+            token,
+        },
+        accounts,
+        amount_to_redeem,
+        &signer_seeds,
+        &mut lamport_moves,
+    )?;
+    // lamports move directly only once the accounts above are created
+    settle_payouts(&cash_link, cash_link_info, accounts, rent_info, lamport_moves)?;
+    redemption.redeemed_at = clock.unix_timestamp as u64;
+    redemption.amount = redemption.amount.error_add(amount_to_redeem)?;
+    redemption.total_claims = redemption.total_claims.error_increment()?;
+    Redemption::pack(redemption, &mut redemption_info.data.borrow_mut())?;
+    if cash_link.is_fully_redeemed()? {
+        cash_link.state = transition(&cash_link.state, CashLinkEvent::Settle)?;
+    }
+    cash_link.last_redeemed_at = Some(clock.unix_timestamp as u64);
+    CashLink::pack(cash_link, &mut cash_link_info.data.borrow_mut())?;
+    Ok(())
+}
+
+/// Load the redemption record of `wallet` on a cash link, or start a new one.
+/// Only the canonical address and `bump` are accepted, so a wallet has a
+/// single record
+pub fn load_redemption(
+    redemption_info: &AccountInfo,
+    cash_link_info: &AccountInfo,
+    wallet: &Pubkey,
+    bump: u8,
+    program_id: &Pubkey,
+) -> Result<Redemption, ProgramError> {
+    let (redemption_key, redemption_bump) =
+        find_cash_link_redemption_program_address(program_id, cash_link_info.key, wallet);
+    if *redemption_info.key != redemption_key || bump != redemption_bump {
+        return Err(CashError::InvalidRedemption.into());
+    }
+    if exists(redemption_info)? && !redemption_info.data_is_empty() {
+        assert_owned_by(redemption_info, program_id)?;
+        // legacy links flagged the single claim of a wallet with a one byte account
+        if redemption_info.data_len() == FLAG_ACCOUNT_SIZE {
+            return Ok(Redemption {
+                account_type: AccountType::Redemption,
+                cash_link: *cash_link_info.key,
+                wallet: *wallet,
+                total_claims: 1,
+                ..Redemption::default()
+            });
+        }
+        let redemption = Redemption::unpack(&redemption_info.data.borrow())?;
+        if redemption.cash_link != *cash_link_info.key || redemption.wallet != *wallet {
+            return Err(CashError::InvalidRedemption.into());
+        }
+        Ok(redemption)
+    } else {
+        Ok(Redemption {
+            account_type: AccountType::Redemption,
+            cash_link: *cash_link_info.key,
+            wallet: *wallet,
+            ..Redemption::default()
+        })
+    }
+}
+
+/// Amount paid by the next redemption of a cash link: the allowlisted amount
+/// if any, else an equal share or a random one drawn from `random`
+pub fn redemption_amount(
+    cash_link: &CashLink,
+    allowlisted_amount: Option<u64>,
+    random: impl FnOnce() -> Result<u64, ProgramError>,
+) -> Result<u64, ProgramError> {
     if cash_link.total_redemptions >= cash_link.max_num_redemptions {
         return Err(CashError::MaxRedemptionsReached.into());
     }
     if cash_link.remaining_amount == 0 {
         return Err(CashError::NoRemainingAmount.into());
     }
-
-    let amount_to_redeem = match cash_link.distribution_type {
-        DistributionType::Fixed => cash_link
-            .amount
-            .checked_div(cash_link.max_num_redemptions as u64)
-            .ok_or(CashError::Overflow)?,
-        DistributionType::Random => {
-            if cash_link.max_num_redemptions == 1
-                || cash_link.total_redemptions == (cash_link.max_num_redemptions - 1)
-            {
-                cash_link.remaining_amount
-            } else {
-                // get slot hash
-                let rand = get_random_value(recent_slothashes_info, clock)?;
-                let max_possible = cash_link.remaining_amount;
-
-                rand.checked_rem(max_possible - cash_link.min_amount)
-                    .and_then(|amount| amount.checked_add(cash_link.min_amount))
-                    .ok_or(CashError::Overflow)?
+    let amount = match allowlisted_amount {
+        Some(amount) => {
+            if amount > cash_link.remaining_amount {
+                return Err(InsufficientSettlementFunds.into());
             }
+            amount
         }
+        None => match cash_link.distribution_type {
+            DistributionType::Fixed => cash_link
+                .amount
+                .checked_div(cash_link.max_num_redemptions as u64)
+                .ok_or(CashError::Overflow)?,
+            DistributionType::Random => {
+                if cash_link.max_num_redemptions == 1
+                    || cash_link.total_redemptions == (cash_link.max_num_redemptions - 1)
+                {
+                    cash_link.remaining_amount
+                } else {
+                    let rand = random()?;
+                    let max_possible = cash_link.remaining_amount;
+
+                    rand.checked_rem(max_possible - cash_link.min_amount)
+                        .and_then(|amount| amount.checked_add(cash_link.min_amount))
+                        .ok_or(CashError::Overflow)?
+                }
+            }
+        },
     };
 
-    let fee_to_redeem = cash_link.fee_to_redeem;
+    Ok(amount)
+}
+
+/// Accounts paying out a single redemption
+pub struct PayoutAccounts<'a, 'b> {
+    pub cash_link_info: &'b AccountInfo<'a>,
+    pub recipient_info: &'b AccountInfo<'a>,
+    pub fee_token_info: &'b AccountInfo<'a>,
+    pub owner_token_info: &'b AccountInfo<'a>,
+    pub fee_payer_info: &'b AccountInfo<'a>,
+    pub rent_info: &'b AccountInfo<'a>,
+    /// `(recipient token, vault, mint)` accounts of a token link
+    pub token: Option<(&'b AccountInfo<'a>, &'b AccountInfo<'a>, &'b AccountInfo<'a>)>,
+}
+
+/// Pay `amount_to_redeem` and the fees of the next redemption out of a cash
+/// link, settling what is left with its refund address once it is fully
+/// redeemed. `accounts` are all of the instruction accounts, searched for the
+/// refund wallet and the trailing bundle accounts. Lamports are pushed to
+/// `lamport_moves` for `settle_payouts` to move once every CPI has run
+pub fn pay_out<'a, 'b>(
+    cash_link: &mut CashLink,
+    payout: &PayoutAccounts<'a, 'b>,
+    accounts: &'b [AccountInfo<'a>],
+    amount_to_redeem: u64,
+    signer_seeds: &[&[u8]],
+    lamport_moves: &mut Vec<(&'b AccountInfo<'a>, u64)>,
+) -> ProgramResult {
+    let PayoutAccounts {
+        cash_link_info,
+        recipient_info,
+        fee_token_info,
+        owner_token_info,
+        fee_payer_info,
+        rent_info,
+        token,
+    } = *payout;
+    let index = cash_link.total_redemptions;
+    let remaining_amount = cash_link.remaining_amount;
+    // lamports earlier payouts of the instruction still owe
+    let pending = lamport_moves
+        .iter()
+        .try_fold(0u64, |total, (_, amount)| total.error_add(*amount))?;
 
     cash_link.remaining_amount = cash_link
         .remaining_amount
@@ -441,44 +921,39 @@ pub fn process_redemption(
 
     cash_link.total_redemptions = cash_link.total_redemptions.error_increment()?;
 
-    let platform_fee_per_redeem: u64 = calculate_fee(cash_link.amount, cash_link.fee_bps as u64)?
-        .checked_div(cash_link.max_num_redemptions as u64)
-        .ok_or(CashError::Overflow)?;
-
-    let total_fee_to_redeem = if cash_link.total_redemptions == 1 {
-        platform_fee_per_redeem
-            .checked_add(fee_to_redeem)
-            .ok_or::<ProgramError>(CashError::Overflow.into())?
-            .checked_add(cash_link.fixed_fee)
-            .ok_or::<ProgramError>(CashError::Overflow.into())?
-    } else {
-        platform_fee_per_redeem
-            .checked_add(fee_to_redeem)
-            .ok_or::<ProgramError>(CashError::Overflow.into())?
-    };
+    let quote = redemption_quote(cash_link, index, cash_link.is_fully_redeemed()?)?;
+    let total_fee_to_redeem = quote.total_fee;
+    cash_link.fees_collected = cash_link.fees_collected.error_add(quote.platform_fee)?;
 
+    let relayer_fee = quote.relayer_fee;
+    // only the fee payer relaying the transaction is paid for it
+    if relayer_fee > 0 {
+        assert_signer(fee_payer_info)?;
+    }
     let total = amount_to_redeem
         .checked_add(total_fee_to_redeem)
+        .and_then(|total| total.checked_add(relayer_fee))
         .ok_or::<ProgramError>(CashError::Overflow.into())?;
 
-    if let Some(mint) = cash_link.mint {
-        assert_owned_by(fee_token_info, &spl_token::id())?;
-        let recipient_token_info = next_account_info(account_info_iter)?;
+    if let (Some(mint), Some((recipient_token_info, vault_token_info, mint_info))) =
+        (cash_link.mint, token)
+    {
+        if !cash_link.fees_in_sol {
+            assert_owned_by(fee_token_info, &spl_token::id())?;
+        }
         assert_owned_by(recipient_token_info, &spl_token::id())?;
-        let vault_token_info = next_account_info(account_info_iter)?;
         assert_owned_by(vault_token_info, &spl_token::id())?;
-        let associated_token_account = get_associated_token_address(&cash_link_info.key, &mint);
+        let associated_token_account = get_associated_token_address(cash_link_info.key, &mint);
         assert_account_key(
             vault_token_info,
             &associated_token_account,
             Some(CashError::InvalidVaultTokenOwner),
         )?;
         let vault_token: TokenAccount = assert_initialized(vault_token_info)?;
-        let mint_info = next_account_info(account_info_iter)?;
         if exists(recipient_token_info)? {
             msg!("Cash link has a mint and an existing recipient token. Validate the recipient token");
             let recipient_token: TokenAccount = assert_initialized(recipient_token_info)?;
-            assert_token_owned_by(&recipient_token, &wallet_info.key)?;
+            assert_token_owned_by(&recipient_token, recipient_info.key)?;
             assert_owned_by(recipient_token_info, &spl_token::id())?;
             //subtract rent_fee
         } else {
@@ -486,166 +961,298 @@ pub fn process_redemption(
             create_associated_token_account_raw(
                 fee_payer_info,
                 recipient_token_info,
-                wallet_info,
+                recipient_info,
                 mint_info,
                 rent_info,
             )?;
         }
+        let (token_fee, sol_fee) = if cash_link.fees_in_sol {
+            (0, total_fee_to_redeem)
+        } else {
+            let _: TokenAccount = assert_initialized(fee_token_info)?;
+            (total_fee_to_redeem, 0)
+        };
+        let total = amount_to_redeem.error_add(token_fee)?;
         if vault_token.amount < total {
             return Err(InsufficientSettlementFunds.into());
         }
-        let _: TokenAccount = assert_initialized(fee_token_info)?;
         if amount_to_redeem > 0 {
             spl_token_transfer(
                 vault_token_info,
                 recipient_token_info,
                 cash_link_info,
                 amount_to_redeem,
-                &[&signer_seeds],
+                &[signer_seeds],
             )?;
         }
-        if total_fee_to_redeem > 0 {
+        if token_fee > 0 {
             spl_token_transfer(
                 vault_token_info,
                 fee_token_info,
                 cash_link_info,
-                total_fee_to_redeem,
-                &[&signer_seeds],
+                token_fee,
+                &[signer_seeds],
             )?;
         }
+        let rent = &Rent::from_account_info(rent_info)?;
+        if fee_deposit_balance(cash_link, cash_link_info, rent)?.error_sub(pending)?
+            < sol_fee.error_add(relayer_fee)?
+        {
+            return Err(InsufficientSettlementFunds.into());
+        }
+        if sol_fee > 0 {
+            lamport_moves.push((fee_token_info, sol_fee));
+        }
+        if relayer_fee > 0 {
+            lamport_moves.push((fee_payer_info, relayer_fee));
+        }
         let remaining = vault_token
             .amount
             .checked_sub(total)
             .ok_or::<ProgramError>(CashError::Overflow.into())?;
         if cash_link.is_fully_redeemed()? {
             let owner_token: TokenAccount = assert_initialized(owner_token_info)?;
-            assert_token_owned_by(&owner_token, &cash_link.owner)?;
+            assert_token_owned_by(&owner_token, cash_link.refund_address())?;
             if remaining > 0 {
                 spl_token_transfer(
                     vault_token_info,
                     owner_token_info,
                     cash_link_info,
                     remaining,
-                    &[&signer_seeds],
+                    &[signer_seeds],
                 )?;
             }
             spl_token_close(
                 vault_token_info,
                 fee_payer_info,
                 cash_link_info,
-                &[&signer_seeds],
+                &[signer_seeds],
             )?;
         }
-    } else {
+    } else if cash_link.mint.is_none() {
+        // the amount of a stake link is its stake account, not lamports
+        let (amount_to_redeem, total) = match cash_link.stake {
+            Some(_) => {
+                release_stake(cash_link, cash_link_info, accounts, recipient_info.key, signer_seeds)?;
+                (0, total.error_sub(amount_to_redeem)?)
+            }
+            None => (amount_to_redeem, total),
+        };
         let rent = &Rent::from_account_info(rent_info)?;
-        let min_lamports = rent.minimum_balance(CashLink::LEN);
-        let mut source_starting_lamports = cash_link_info.lamports();
-        let available_amount = source_starting_lamports
-            .checked_sub(min_lamports)
+        let available_amount = cash_link_info
+            .lamports()
+            .checked_sub(rent.minimum_balance(CashLink::LEN))
+            .and_then(|available| available.checked_sub(pending))
             .ok_or(AmountOverflow)?;
         if available_amount < total {
             return Err(InsufficientSettlementFunds.into());
         }
         if amount_to_redeem > 0 {
-            let dest_starting_lamports = wallet_info.lamports();
-            **wallet_info.lamports.borrow_mut() = dest_starting_lamports
-                .checked_add(amount_to_redeem)
-                .ok_or(AmountOverflow)?;
-            source_starting_lamports = source_starting_lamports
-                .checked_sub(amount_to_redeem)
-                .ok_or(AmountOverflow)?;
+            lamport_moves.push((recipient_info, amount_to_redeem));
         }
         if total_fee_to_redeem > 0 {
-            let dest_starting_lamports = fee_token_info.lamports();
-            **fee_token_info.lamports.borrow_mut() = dest_starting_lamports
-                .checked_add(total_fee_to_redeem)
-                .ok_or(AmountOverflow)?;
-            source_starting_lamports = source_starting_lamports
-                .checked_sub(total_fee_to_redeem)
-                .ok_or(AmountOverflow)?;
+            lamport_moves.push((fee_token_info, total_fee_to_redeem));
+        }
+        if relayer_fee > 0 {
+            lamport_moves.push((fee_payer_info, relayer_fee));
         }
         let remaining = available_amount.checked_sub(total).ok_or(AmountOverflow)?;
         if cash_link.is_fully_redeemed()? && remaining > 0 {
-            let dest_starting_lamports = owner_token_info.lamports();
-            **owner_token_info.lamports.borrow_mut() = dest_starting_lamports
-                .checked_add(remaining)
-                .ok_or(AmountOverflow)?;
-            source_starting_lamports = source_starting_lamports
-                .checked_sub(remaining)
-                .ok_or(AmountOverflow)?;
+            assert_account_key(owner_token_info, cash_link.refund_address(), Some(CashError::InvalidOwner))?;
+            lamport_moves.push((owner_token_info, remaining));
         }
-        **cash_link_info.lamports.borrow_mut() = source_starting_lamports;
-    }
-    let system_account_info = next_account_info(account_info_iter)?;
-    if redemption_info.lamports() > 0
-    && !redemption_info.data_is_empty()
-    {
-        msg!("Redemption AccountAlreadyInitialized");
-        return Err(ProgramError::AccountAlreadyInitialized);
+    } else {
+        return Err(ProgramError::NotEnoughAccountKeys);
     }
-    create_new_account_raw(
-        program_id,
-        redemption_info,
-        rent_info,
+    let last = cash_link.is_fully_redeemed()?;
+    redeem_bundle(
+        cash_link,
+        cash_link_info,
+        recipient_info,
         fee_payer_info,
-        system_account_info,
-        FLAG_ACCOUNT_SIZE,
-        &[
-            REDEMPTION_PREFIX.as_bytes(),
-            cash_link_info.key.as_ref(),
-            wallet_info.key.as_ref(),
-            &[args.redemption_bump],
-        ],
+        rent_info,
+        accounts,
+        amount_to_redeem,
+        remaining_amount,
+        last,
+        signer_seeds,
+        lamport_moves,
     )?;
-    if cash_link.fingerprint_enabled {
-        if let Some(bump) = args.fingerprint_bump {
-            if let Some(fingerprint) = args.fingerprint {
-                let fingerprint_account_info = next_account_info(account_info_iter)?;
-                if fingerprint_account_info.lamports() > 0
-                    && !fingerprint_account_info.data_is_empty()
-                {
-                    msg!("Fingerprint AccountAlreadyInitialized");
-                    return Err(ProgramError::AccountAlreadyInitialized);
-                }
-                create_new_account_raw(
-                    program_id,
-                    fingerprint_account_info,
-                    rent_info,
-                    fee_payer_info,
-                    system_account_info,
-                    FLAG_ACCOUNT_SIZE,
-                    &[
-                        FINGERPRINT_PREFIX.as_bytes(),
-                        cash_link_info.key.as_ref(),
-                        &bs58::decode(fingerprint)
-                            .into_vec()
-                            .map_err(|_| CashError::InvalidFingerprint)?,
-                        &[bump],
-                    ],
-                )?;
-            } else {
-                return Err(CashError::FingerprintFound.into());
-            }
-        } else {
-            return Err(CashError::FingerprintBumpNotFound.into());
-        }
-        if args.fingerprint_bump.is_none() {
-            return Err(CashError::FingerprintBumpNotFound.into());
-        }
+    Ok(())
+}
+
+/// Move the lamports payouts pushed to `lamport_moves` out of the cash link,
+/// once every CPI of the instruction has run, and return what is left of the
+/// fee deposit of a link they fully redeemed
+pub fn settle_payouts<'a>(
+    cash_link: &CashLink,
+    cash_link_info: &AccountInfo<'a>,
+    accounts: &[AccountInfo<'a>],
+    rent_info: &AccountInfo<'a>,
+    lamport_moves: Vec<(&AccountInfo<'a>, u64)>,
+) -> ProgramResult {
+    for (info, amount) in lamport_moves {
+        move_lamports(cash_link_info, info, amount)?;
+    }
+    if cash_link.is_fully_redeemed()? && cash_link.has_fee_deposit() {
+        refund_fee_deposit(cash_link, cash_link_info, accounts, &Rent::from_account_info(rent_info)?)?;
+    }
+    Ok(())
+}
+
+/// Lamports left of the fee deposit of a token link, above its rent and the
+/// native bundle assets it escrows
+fn fee_deposit_balance(
+    cash_link: &CashLink,
+    cash_link_info: &AccountInfo,
+    rent: &Rent,
+) -> Result<u64, ProgramError> {
+    cash_link_info
+        .lamports()
+        .error_sub(rent.minimum_balance(CashLink::LEN))?
+        .error_sub(cash_link.native_bundle_remaining()?)
+}
+
+/// Return what is left of the fee deposit to the refund wallet, which is
+/// looked up by key among the instruction accounts
+fn refund_fee_deposit(
+    cash_link: &CashLink,
+    cash_link_info: &AccountInfo,
+    accounts: &[AccountInfo],
+    rent: &Rent,
+) -> ProgramResult {
+    let balance = fee_deposit_balance(cash_link, cash_link_info, rent)?;
+    if balance > 0 {
+        let refund_info =
+            find_account(accounts, cash_link.refund_address()).ok_or(CashError::InvalidOwner)?;
+        move_lamports(cash_link_info, refund_info, balance)?;
+    }
+    Ok(())
+}
+
+/// Refund an expired cash link to its refund address. Anyone may crank it and
+/// is paid the link's refund bounty
+pub fn process_refund(
+    accounts: &[AccountInfo],
+    program_id: &Pubkey,
+    args: RefundArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let cranker_info = next_account_info(account_info_iter)?;
+    assert_signer(cranker_info)?;
+    let cash_link_info = next_account_info(account_info_iter)?;
+    assert_owned_by(cash_link_info, program_id)?;
+    assert_migrated(cash_link_info)?;
+    let pass_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let owner_token_info = next_account_info(account_info_iter)?;
+    let clock_info = next_account_info(account_info_iter)?;
+    let clock = &Clock::from_account_info(clock_info)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let rent = &Rent::from_account_info(rent_info)?;
+
+    let mut cash_link = CashLink::unpack(&cash_link_info.data.borrow())?;
+    assert_account_key(pass_info, &cash_link.pass_key, Some(CashError::InvalidPassKey))?;
+    assert_account_key(owner_info, cash_link.refund_address(), Some(CashError::InvalidOwner))?;
+    let state = transition(&cash_link.state, CashLinkEvent::Refund)?;
+    if (clock.unix_timestamp as u64) <= cash_link.expires_at {
+        return Err(CashError::CashlinkNotExpired.into());
+    }
+
+    let signer_seeds = [
+        CashLink::PREFIX.as_bytes(),
+        pass_info.key.as_ref(),
+        &[args.cash_link_bump],
+    ];
+    if cash_link.stake.is_some() {
+        release_stake(&cash_link, cash_link_info, accounts, cash_link.refund_address(), &signer_seeds)?;
+    }
+    let bounty = cash_link
+        .refund_bounty
+        .min(fee_deposit_balance(&cash_link, cash_link_info, rent)?);
+    let mut lamport_moves = Vec::new();
+    if bounty > 0 {
+        msg!("Pay a refund bounty of {}", bounty);
+        lamport_moves.push((cranker_info, bounty));
     }
-    // let mut redemption = Redemption::unpack_unchecked(&redemption_info.data.borrow_mut())?;
-    // redemption.account_type = AccountType::Redemption;
-    // redemption.cash_link = *cash_link_info.key;
-    // redemption.redeemed_at = clock.unix_timestamp as u64;
-    // redemption.wallet = *wallet_info.key;
-    // redemption.amount = amount_to_redeem;
-    // Redemption::pack(redemption, &mut redemption_info.data.borrow_mut())?;
-    cash_link.state = if cash_link.is_fully_redeemed()? {
-        CashLinkState::Redeemed
+
+    let vault_token_info = match cash_link.mint {
+        Some(_) => Some(next_account_info(account_info_iter)?),
+        None => None,
+    };
+    refund_owner(
+        &mut cash_link,
+        cash_link_info,
+        owner_token_info,
+        vault_token_info,
+        owner_info,
+        rent,
+        accounts,
+        &signer_seeds,
+        lamport_moves,
+    )?;
+    msg!("Mark the cash_link account as expired...");
+    cash_link.state = state;
+    cash_link.ended_at = Some(clock.unix_timestamp as u64);
+    CashLink::pack(cash_link, &mut cash_link_info.data.borrow_mut())?;
+    Ok(())
+}
+
+/// Freeze a cash link for review, or release it back to the state it had
+/// before being frozen
+pub fn process_set_frozen(
+    accounts: &[AccountInfo],
+    program_id: &Pubkey,
+    args: SetFrozenArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let authority_info = next_account_info(account_info_iter)?;
+    assert_signer(authority_info)?;
+    let cash_link_info = next_account_info(account_info_iter)?;
+    assert_owned_by(cash_link_info, program_id)?;
+    assert_migrated(cash_link_info)?;
+
+    let mut cash_link = CashLink::unpack(&cash_link_info.data.borrow())?;
+    assert_account_key(
+        authority_info,
+        &cash_link.authority,
+        Some(CashError::InvalidAuthorityId),
+    )?;
+    let event = if args.frozen {
+        CashLinkEvent::Freeze
     } else {
-        CashLinkState::Redeeming
+        CashLinkEvent::Unfreeze {
+            has_redemptions: cash_link.total_redemptions > 0,
+        }
     };
-    cash_link.last_redeemed_at = Some(clock.unix_timestamp as u64);
+    cash_link.state = transition(&cash_link.state, event)?;
+    msg!("Cash link frozen: {}", args.frozen);
+    CashLink::pack(cash_link, &mut cash_link_info.data.borrow_mut())?;
+    Ok(())
+}
+
+/// Reallocate a cash link created with the legacy layout to the current one,
+/// the fee payer paying the rent of the added space
+pub fn process_migrate_cash_link(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let fee_payer_info = next_account_info(account_info_iter)?;
+    assert_signer(fee_payer_info)?;
+    let cash_link_info = next_account_info(account_info_iter)?;
+    assert_owned_by(cash_link_info, program_id)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let rent = &Rent::from_account_info(rent_info)?;
+    let _system_account_info = next_account_info(account_info_iter)?;
+
+    if cash_link_info.data_len() != LEGACY_CASH_LINK_DATA_SIZE {
+        return Err(CashError::CashLinkAlreadyMigrated.into());
+    }
+    let cash_link = CashLink::unpack(&cash_link_info.data.borrow())?;
+    let added_rent = rent
+        .minimum_balance(CashLink::LEN)
+        .error_sub(rent.minimum_balance(LEGACY_CASH_LINK_DATA_SIZE))?;
+    msg!("Migrate the cash_link account to {} bytes", CashLink::LEN);
+    native_transfer(fee_payer_info, cash_link_info, added_rent, &[])?;
+    cash_link_info.realloc(CashLink::LEN, true)?;
     CashLink::pack(cash_link, &mut cash_link_info.data.borrow_mut())?;
     Ok(())
 }
@@ -665,9 +1272,7 @@ pub fn process_close(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramRe
         &cash_link.authority,
         Some(CashError::InvalidAuthorityId),
     )?;
-    if !cash_link.expired() {
-        return Err(AccountNotExpired.into());
-    }
+    transition(&cash_link.state, CashLinkEvent::Close)?;
     if cash_link.total_redemptions > 0 {
         return Err(AccountAlreadyRedeemed.into());
     }
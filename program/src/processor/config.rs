@@ -0,0 +1,130 @@
+//! Program config and the global pause
+//!
+//! The config account trails the accounts of every instruction creating or
+//! redeeming cash links. `Processor::process` checks it and strips it before
+//! dispatching, so the trailing bundle accounts of those instructions are
+//! unaffected.
+//!
+//! Until `SetConfig` first creates it, the config account is empty and the
+//! program runs unpaused.
+use std::convert::TryInto;
+
+use crate::{
+    error::CashError,
+    find_config_program_address,
+    instruction::SetConfigArgs,
+    state::{config::Config, AccountType},
+    utils::{assert_account_key, assert_owned_by, assert_signer, cmp_pubkeys, create_new_account_raw},
+};
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    bpf_loader_upgradeable,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+/// Offset of the upgrade authority option in a program data account
+const UPGRADE_AUTHORITY_OFFSET: usize = 12;
+
+/// Reject the instruction while the program is paused. A config that was
+/// never set does not pause the program. Returns the instruction accounts
+/// without the trailing config account
+pub fn assert_not_paused<'a, 'b>(
+    accounts: &'b [AccountInfo<'a>],
+    program_id: &Pubkey,
+) -> Result<&'b [AccountInfo<'a>], ProgramError> {
+    let (config_info, accounts) = accounts
+        .split_last()
+        .ok_or(ProgramError::NotEnoughAccountKeys)?;
+    assert_account_key(
+        config_info,
+        &find_config_program_address(program_id).0,
+        Some(CashError::InvalidConfig),
+    )?;
+    if !cmp_pubkeys(config_info.owner, program_id) {
+        return Ok(accounts);
+    }
+    let config = Config::unpack(&config_info.data.borrow())
+        .map_err(|_| ProgramError::from(CashError::InvalidConfig))?;
+    if config.paused {
+        return Err(CashError::ProgramPaused.into());
+    }
+    Ok(accounts)
+}
+
+/// The upgrade authority set in the program data account of `program_id`
+fn upgrade_authority(
+    program_data_info: &AccountInfo,
+    program_id: &Pubkey,
+) -> Result<Option<Pubkey>, ProgramError> {
+    assert_account_key(
+        program_data_info,
+        &Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id()).0,
+        Some(CashError::InvalidConfig),
+    )?;
+    assert_owned_by(program_data_info, &bpf_loader_upgradeable::id())?;
+    let data = program_data_info.data.borrow();
+    match data.get(UPGRADE_AUTHORITY_OFFSET) {
+        Some(1) => data
+            .get(UPGRADE_AUTHORITY_OFFSET + 1..UPGRADE_AUTHORITY_OFFSET + 33)
+            .map(|key| Some(Pubkey::new_from_array(key.try_into().unwrap())))
+            .ok_or_else(|| CashError::InvalidConfig.into()),
+        Some(0) => Ok(None),
+        _ => Err(CashError::InvalidConfig.into()),
+    }
+}
+
+/// Create or update the program config. The config is created by the
+/// program's upgrade authority and later updated by its admin
+pub fn process_set_config(
+    accounts: &[AccountInfo],
+    args: SetConfigArgs,
+    program_id: &Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    assert_signer(signer_info)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let (config_key, config_bump) = find_config_program_address(program_id);
+    assert_account_key(config_info, &config_key, Some(CashError::InvalidConfig))?;
+    if args.config_bump != config_bump {
+        return Err(CashError::InvalidConfig.into());
+    }
+    let fee_payer_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+
+    let mut config = if cmp_pubkeys(config_info.owner, program_id) {
+        let config = Config::unpack(&config_info.data.borrow())?;
+        assert_account_key(signer_info, &config.admin, Some(CashError::InvalidAuthorityId))?;
+        config
+    } else {
+        let program_data_info = next_account_info(account_info_iter)?;
+        if upgrade_authority(program_data_info, program_id)? != Some(*signer_info.key) {
+            return Err(CashError::InvalidAuthorityId.into());
+        }
+        msg!("Create the program config");
+        create_new_account_raw(
+            program_id,
+            config_info,
+            rent_info,
+            fee_payer_info,
+            system_account_info,
+            Config::LEN,
+            &[Config::PREFIX.as_bytes(), &[args.config_bump]],
+        )?;
+        Config {
+            account_type: AccountType::Config,
+            ..Config::default()
+        }
+    };
+    config.admin = args.admin;
+    config.paused = args.paused;
+    msg!("Program paused: {}", config.paused);
+    Config::pack(config, &mut config_info.data.borrow_mut())?;
+    Ok(())
+}
@@ -0,0 +1,176 @@
+//! Authority pushed payouts
+//!
+//! The authority pays an existing cash link out to known wallets, without
+//! pass keys or claims. Each payout is a regular redemption: it is recorded
+//! on the wallet's redemption account and charges the same fees.
+use crate::{
+    error::CashError,
+    instruction::DistributeArgs,
+    math::SafeMath,
+    processor::cashlink::{
+        load_redemption, pay_out, redemption_amount, settle_payouts, PayoutAccounts,
+    },
+    state::{
+        cashlink::CashLink,
+        redemption::Redemption,
+        transitions::{transition, CashLinkEvent},
+        REDEMPTION_PREFIX,
+    },
+    utils::{
+        assert_account_key, assert_migrated, assert_owned_by, assert_signer, create_new_account_raw,
+        get_random_value,
+    },
+};
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::{clock::Clock, slot_hashes, Sysvar},
+};
+
+pub fn process_distribute(
+    accounts: &[AccountInfo],
+    args: DistributeArgs,
+    program_id: &Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let authority_info = next_account_info(account_info_iter)?;
+    assert_signer(authority_info)?;
+    let cash_link_info = next_account_info(account_info_iter)?;
+    assert_owned_by(cash_link_info, program_id)?;
+    assert_migrated(cash_link_info)?;
+    let pass_info = next_account_info(account_info_iter)?;
+    let fee_token_info = next_account_info(account_info_iter)?;
+    let owner_token_info = next_account_info(account_info_iter)?;
+    let fee_payer_info = next_account_info(account_info_iter)?;
+    let clock_info = next_account_info(account_info_iter)?;
+    let clock = &Clock::from_account_info(clock_info)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let recent_slothashes_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+    let _token_program_info = next_account_info(account_info_iter)?;
+    let _associated_token_program_info = next_account_info(account_info_iter)?;
+
+    let mut cash_link = CashLink::unpack(&cash_link_info.data.borrow())?;
+    assert_account_key(
+        authority_info,
+        &cash_link.authority,
+        Some(CashError::InvalidAuthorityId),
+    )?;
+    assert_account_key(pass_info, &cash_link.pass_key, Some(CashError::InvalidPassKey))?;
+    assert_account_key(
+        recent_slothashes_info,
+        &slot_hashes::id(),
+        Some(CashError::InvalidSlotHashProgram),
+    )?;
+    cash_link.state = transition(&cash_link.state, CashLinkEvent::Redeem)?;
+    if clock.unix_timestamp as u64 > cash_link.expires_at {
+        return Err(CashError::CashlinkExpired.into());
+    }
+    // bundle accounts trail a single recipient, they can't be pushed in bulk
+    if !cash_link.bundle.is_empty() {
+        return Err(CashError::InvalidBundle.into());
+    }
+    if cash_link.stake.is_some() {
+        return Err(CashError::InvalidStake.into());
+    }
+    // the allowlist and cooldowns gate claims, which pushed payouts bypass
+    if cash_link.allowlist_root.is_some()
+        || cash_link.cooldown_seconds > 0
+        || cash_link.wallet_cooldown_seconds > 0
+    {
+        return Err(CashError::GatedDistribution.into());
+    }
+
+    let vault = match cash_link.mint {
+        Some(_) => Some((
+            next_account_info(account_info_iter)?,
+            next_account_info(account_info_iter)?,
+        )),
+        None => None,
+    };
+    let signer_seeds = [
+        CashLink::PREFIX.as_bytes(),
+        pass_info.key.as_ref(),
+        &[args.cash_link_bump],
+    ];
+
+    msg!("Distribute to {} wallets", args.redemption_bumps.len());
+    let mut lamport_moves = Vec::new();
+    for redemption_bump in args.redemption_bumps {
+        let wallet_info = next_account_info(account_info_iter)?;
+        let redemption_info = next_account_info(account_info_iter)?;
+        let mut redemption = load_redemption(
+            redemption_info,
+            cash_link_info,
+            wallet_info.key,
+            redemption_bump,
+            program_id,
+        )?;
+        if redemption.total_claims >= cash_link.max_claims_per_wallet {
+            return Err(CashError::MaxClaimsPerWalletReached.into());
+        }
+        // every wallet of the batch draws its own random amount
+        let amount_to_redeem = redemption_amount(&cash_link, None, || {
+            let mut salt = [0u8; 8];
+            salt.copy_from_slice(&wallet_info.key.as_ref()[..8]);
+            Ok(get_random_value(recent_slothashes_info, clock)? ^ u64::from_le_bytes(salt))
+        })?;
+        let token = match vault {
+            Some((vault_token_info, mint_info)) => Some((
+                next_account_info(account_info_iter)?,
+                vault_token_info,
+                mint_info,
+            )),
+            None => None,
+        };
+        if redemption.total_claims == 0 {
+            create_new_account_raw(
+                program_id,
+                redemption_info,
+                rent_info,
+                fee_payer_info,
+                system_account_info,
+                Redemption::LEN,
+                &[
+                    REDEMPTION_PREFIX.as_bytes(),
+                    cash_link_info.key.as_ref(),
+                    wallet_info.key.as_ref(),
+                    &[redemption_bump],
+                ],
+            )?;
+        }
+        pay_out(
+            &mut cash_link,
+            &PayoutAccounts {
+                cash_link_info,
+                recipient_info: wallet_info,
+                fee_token_info,
+                owner_token_info,
+                fee_payer_info,
+                rent_info,
+                token,
+            },
+            accounts,
+            amount_to_redeem,
+            &signer_seeds,
+            &mut lamport_moves,
+        )?;
+        redemption.redeemed_at = clock.unix_timestamp as u64;
+        redemption.amount = redemption.amount.error_add(amount_to_redeem)?;
+        redemption.total_claims = redemption.total_claims.error_increment()?;
+        Redemption::pack(redemption, &mut redemption_info.data.borrow_mut())?;
+    }
+    // lamports move directly only once every wallet's accounts are created
+    settle_payouts(&cash_link, cash_link_info, accounts, rent_info, lamport_moves)?;
+
+    if cash_link.is_fully_redeemed()? {
+        cash_link.state = transition(&cash_link.state, CashLinkEvent::Settle)?;
+    }
+    cash_link.last_redeemed_at = Some(clock.unix_timestamp as u64);
+    CashLink::pack(cash_link, &mut cash_link_info.data.borrow_mut())?;
+    Ok(())
+}
@@ -0,0 +1,104 @@
+//! Stake accounts gifted by stake cash links
+//!
+//! A stake link escrows a stake account rather than lamports or tokens. Its
+//! staker and withdrawer authorities move from the owner to the cash link PDA
+//! at init, then to the redeemer, or to the link's refund address when it is
+//! cancelled or refunded. The stake account and the stake program are found by
+//! key among the instruction accounts.
+use crate::{
+    error::CashError,
+    state::cashlink::CashLink,
+    utils::{assert_owned_by, find_account},
+};
+
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    stake::{
+        self,
+        instruction::authorize,
+        state::{StakeAuthorize, StakeStateV2},
+    },
+    sysvar,
+};
+
+/// The stake account of a stake link, the clock sysvar and the stake program
+fn stake_accounts<'a, 'b>(
+    stake: &Pubkey,
+    accounts: &'b [AccountInfo<'a>],
+) -> Result<(&'b AccountInfo<'a>, &'b AccountInfo<'a>, &'b AccountInfo<'a>), ProgramError> {
+    let stake_info = find_account(accounts, stake).ok_or(CashError::InvalidStake)?;
+    let clock_info = find_account(accounts, &sysvar::clock::id()).ok_or(CashError::InvalidStake)?;
+    let stake_program_info =
+        find_account(accounts, &stake::program::id()).ok_or(CashError::InvalidStake)?;
+    Ok((stake_info, clock_info, stake_program_info))
+}
+
+/// Hand both authorities of the stake account from `authority_info` to
+/// `new_authority`
+fn authorize_stake<'a>(
+    stake_infos: (&AccountInfo<'a>, &AccountInfo<'a>, &AccountInfo<'a>),
+    authority_info: &AccountInfo<'a>,
+    new_authority: &Pubkey,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let (stake_info, clock_info, stake_program_info) = stake_infos;
+    for stake_authorize in [StakeAuthorize::Staker, StakeAuthorize::Withdrawer] {
+        invoke_signed(
+            &authorize(stake_info.key, authority_info.key, new_authority, stake_authorize, None),
+            &[
+                stake_info.clone(),
+                clock_info.clone(),
+                authority_info.clone(),
+                stake_program_info.clone(),
+            ],
+            signers_seeds,
+        )?;
+    }
+    Ok(())
+}
+
+/// Escrow the stake account of a new stake link. Both its authorities must be
+/// the owner, its lockup must have expired and its balance must be the link's
+/// `amount`
+pub fn deposit_stake<'a>(
+    cash_link: &CashLink,
+    cash_link_info: &AccountInfo<'a>,
+    owner_info: &AccountInfo<'a>,
+    accounts: &[AccountInfo<'a>],
+    clock: &Clock,
+) -> ProgramResult {
+    let stake = cash_link.stake.as_ref().ok_or(CashError::InvalidStake)?;
+    let stake_infos = stake_accounts(stake, accounts)?;
+    let stake_info = stake_infos.0;
+    assert_owned_by(stake_info, &stake::program::id())?;
+    let meta = bincode::deserialize::<StakeStateV2>(&stake_info.data.borrow())
+        .ok()
+        .and_then(|state| state.meta())
+        .ok_or(CashError::InvalidStake)?;
+    if meta.authorized.staker != cash_link.owner
+        || meta.authorized.withdrawer != cash_link.owner
+        || meta.lockup.is_in_force(clock, None)
+        || stake_info.lamports() != cash_link.amount
+    {
+        return Err(CashError::InvalidStake.into());
+    }
+    authorize_stake(stake_infos, owner_info, cash_link_info.key, &[])
+}
+
+/// Hand the stake account of a stake link over to `new_authority`
+pub fn release_stake<'a>(
+    cash_link: &CashLink,
+    cash_link_info: &AccountInfo<'a>,
+    accounts: &[AccountInfo<'a>],
+    new_authority: &Pubkey,
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    let stake = cash_link.stake.as_ref().ok_or(CashError::InvalidStake)?;
+    let stake_infos = stake_accounts(stake, accounts)?;
+    authorize_stake(stake_infos, cash_link_info, new_authority, &[signer_seeds])
+}
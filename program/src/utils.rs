@@ -2,7 +2,7 @@
 
 use std::convert::TryInto;
 
-use crate::error::CashError;
+use crate::{error::CashError, math::SafeMath, state::cashlink::LEGACY_CASH_LINK_DATA_SIZE};
 
 use solana_program::{
     account_info::AccountInfo,
@@ -42,13 +42,23 @@ pub fn assert_signer(account: &AccountInfo) -> ProgramResult {
 
 /// Assert owned by
 pub fn assert_owned_by(account: &AccountInfo, owner: &Pubkey) -> ProgramResult {
-    if !cmp_pubkeys(&account.owner, owner)  {
+    if !cmp_pubkeys(account.owner, owner)  {
         Err(CashError::InvalidOwner.into())
     } else {
         Ok(())
     }
 }
 
+/// Assert a cash link has the current layout, legacy links are migrated
+/// before they can be written
+pub fn assert_migrated(cash_link_info: &AccountInfo) -> ProgramResult {
+    if cash_link_info.data_len() == LEGACY_CASH_LINK_DATA_SIZE {
+        Err(CashError::CashLinkNotMigrated.into())
+    } else {
+        Ok(())
+    }
+}
+
 /// Assert owned by
 pub fn assert_token_owned_by(token: &Account, owner: &Pubkey) -> ProgramResult {
     if !cmp_pubkeys(&token.owner, owner) {
@@ -64,7 +74,7 @@ pub fn assert_account_key(
     key: &Pubkey,
     error: Option<CashError>,
 ) -> ProgramResult {
-    if !cmp_pubkeys(account_info.key, &key) {
+    if !cmp_pubkeys(account_info.key, key) {
         match error {
             Some(e) => Err(e.into()),
             _ => Err(ProgramError::InvalidArgument),
@@ -161,12 +171,19 @@ pub fn native_transfer<'a>(
 ) -> Result<(), ProgramError> {
     invoke_signed(
         // for native SOL transfer user_wallet key == user_token_account key
-        &system_instruction::transfer(&source.key, &destination.key, amount),
+        &system_instruction::transfer(source.key, destination.key, amount),
         &[source.clone(), destination.clone()],
         signers_seeds,
     )
 }
 
+/// Move lamports out of an account owned by the program
+pub fn move_lamports(source: &AccountInfo, destination: &AccountInfo, amount: u64) -> ProgramResult {
+    **source.lamports.borrow_mut() = source.lamports().error_sub(amount)?;
+    **destination.lamports.borrow_mut() = destination.lamports().error_add(amount)?;
+    Ok(())
+}
+
 /// SPL transfer instruction.
 pub fn spl_token_close<'a>(
     source: &AccountInfo<'a>,
@@ -208,20 +225,6 @@ pub fn spl_token_init<'a>(
     )
 }
 
-pub fn calculate_fee(amount: u64, fee_basis_points: u64) -> Result<u64, ProgramError> {
-    Ok(amount
-        .checked_mul(fee_basis_points)
-        .ok_or::<ProgramError>(CashError::Overflow.into())?
-        .checked_div(10000)
-        .ok_or::<ProgramError>(CashError::Overflow.into())?)
-}
-
-pub fn calculate_amount_with_fee(amount: u64, fee_basis_points: u64) -> Result<u64, ProgramError> {
-    Ok(amount
-        .checked_add(calculate_fee(amount, fee_basis_points)?)
-        .ok_or::<ProgramError>(CashError::Overflow.into())?)
-}
-
 pub fn create_new_account_raw<'a>(
     program_id: &Pubkey,
     new_account_info: &AccountInfo<'a>,
@@ -237,7 +240,7 @@ pub fn create_new_account_raw<'a>(
     if required_lamports > 0 {
         msg!("Transfer {} lamports to the new account", required_lamports);
         invoke(
-            &system_instruction::transfer(&payer_info.key, new_account_info.key, required_lamports),
+            &system_instruction::transfer(payer_info.key, new_account_info.key, required_lamports),
             &[
                 payer_info.clone(),
                 new_account_info.clone(),
@@ -252,14 +255,14 @@ pub fn create_new_account_raw<'a>(
     invoke_signed(
         &system_instruction::allocate(new_account_info.key, size.try_into().unwrap()),
         accounts,
-        &[&signer_seeds],
+        &[signer_seeds],
     )?;
 
     msg!("Assign the account to the owning program");
     invoke_signed(
         &system_instruction::assign(new_account_info.key, program_id),
         accounts,
-        &[&signer_seeds],
+        &[signer_seeds],
     )?;
     Ok(())
 }
@@ -289,6 +292,14 @@ pub fn cmp_pubkeys(a: &Pubkey, b: &Pubkey) -> bool {
     sol_memcmp(a.as_ref(), b.as_ref(), PUBKEY_BYTES) == 0
 }
 
+/// Find an account passed anywhere in the instruction by its key
+pub fn find_account<'a, 'b>(
+    accounts: &'b [AccountInfo<'a>],
+    key: &Pubkey,
+) -> Option<&'b AccountInfo<'a>> {
+    accounts.iter().find(|info| cmp_pubkeys(info.key, key))
+}
+
 pub fn exists(account: &AccountInfo) -> Result<bool, ProgramError> {
     Ok(account.try_lamports()? > 0)
 }
@@ -0,0 +1,80 @@
+//! Test program creating cash links through CPI
+//!
+//! The links are owned by the program's rewards PDA, a data account funding
+//! the lamport deposit the way a rewards or escrow program would.
+use borsh::BorshDeserialize;
+use cash_interface::{
+    cpi::{init_cash_link, InitCashLinkAccounts, TokenAccounts},
+    instruction::InitCashLinkArgs,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+pub const REWARDS_PREFIX: &str = "rewards";
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+/// Find the rewards PDA owning the cash links
+pub fn find_rewards_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REWARDS_PREFIX.as_bytes()], program_id)
+}
+
+/// Instruction data is the rewards bump followed by `InitCashLinkArgs`.
+///
+/// Accounts expected:
+///
+/// 0. `[writable]` The rewards PDA
+/// 1. `[signer]` The authority of the cash link
+/// 2. `[writable, signer]` The fee payer
+/// 3. `[writable]` The cash link account
+/// 4. `[]` The pass key
+/// 5. `[]` Rent sysvar
+/// 6. `[]` System program
+/// 7. `[]` Clock sysvar
+/// 8. `[]` The cash program config
+/// 9. `[]` The cash program
+/// 10. `[]` (Optional) The token mint, followed by the vault, the rewards
+///     token account, the associated token and the token programs
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (bump, args) = instruction_data
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    let args = InitCashLinkArgs::try_from_slice(args)?;
+    let account_info_iter = &mut accounts.iter();
+    let rewards = next_account_info(account_info_iter)?;
+    if rewards.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+    let accounts = InitCashLinkAccounts {
+        owner: rewards,
+        authority: next_account_info(account_info_iter)?,
+        fee_payer: next_account_info(account_info_iter)?,
+        cash_link: next_account_info(account_info_iter)?,
+        pass_key: next_account_info(account_info_iter)?,
+        rent: next_account_info(account_info_iter)?,
+        system_program: next_account_info(account_info_iter)?,
+        clock: next_account_info(account_info_iter)?,
+        config: next_account_info(account_info_iter)?,
+        cash_program: next_account_info(account_info_iter)?,
+        token: match account_info_iter.next() {
+            Some(mint) => Some(TokenAccounts {
+                mint,
+                vault: next_account_info(account_info_iter)?,
+                owner_token: next_account_info(account_info_iter)?,
+                associated_token_program: next_account_info(account_info_iter)?,
+                token_program: next_account_info(account_info_iter)?,
+            }),
+            None => None,
+        },
+    };
+    init_cash_link(&accounts, args, &[&[REWARDS_PREFIX.as_bytes(), &[*bump]]])
+}
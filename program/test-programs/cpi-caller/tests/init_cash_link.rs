@@ -0,0 +1,150 @@
+use borsh::BorshSerialize;
+use cash::{
+    error::CashError,
+    find_cash_link_program_address, find_config_program_address,
+    instruction::InitCashLinkArgs,
+    processor::Processor,
+    state::{
+        cashlink::{CashLink, CashLinkState, DistributionType},
+        config::Config,
+        AccountType,
+    },
+};
+use cpi_caller::find_rewards_address;
+use solana_program::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_program, sysvar,
+};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+const REWARDS_LAMPORTS: u64 = 10_000_000_000;
+const AMOUNT: u64 = 1_000_000_000;
+
+fn program_test(caller_id: &Pubkey, paused: bool) -> ProgramTest {
+    let mut program_test = ProgramTest::new("cash", cash::id(), processor!(Processor::process));
+    program_test.add_program(
+        "cpi_caller",
+        *caller_id,
+        processor!(cpi_caller::process_instruction),
+    );
+    let mut config = vec![0; Config::LEN];
+    Config {
+        account_type: AccountType::Config,
+        admin: Pubkey::new_unique(),
+        paused,
+    }
+    .pack_into_slice(&mut config);
+    program_test.add_account(
+        find_config_program_address(&cash::id()).0,
+        Account {
+            lamports: Rent::default().minimum_balance(Config::LEN),
+            data: config,
+            owner: cash::id(),
+            ..Account::default()
+        },
+    );
+    program_test.add_account(
+        find_rewards_address(caller_id).0,
+        Account {
+            lamports: REWARDS_LAMPORTS,
+            data: vec![1; 8],
+            owner: *caller_id,
+            ..Account::default()
+        },
+    );
+    program_test
+}
+
+fn init_args(cash_link_bump: u8) -> InitCashLinkArgs {
+    InitCashLinkArgs {
+        amount: AMOUNT,
+        cash_link_bump,
+        distribution_type: DistributionType::Fixed,
+        max_num_redemptions: 1,
+        num_days_to_expire: 1,
+        ..InitCashLinkArgs::default()
+    }
+}
+
+fn init_instruction(caller_id: &Pubkey, payer: &Pubkey, pass_key: &Pubkey) -> Instruction {
+    let (rewards, rewards_bump) = find_rewards_address(caller_id);
+    let (cash_link, cash_link_bump) = find_cash_link_program_address(&cash::id(), *pass_key);
+    let mut data = vec![rewards_bump];
+    data.extend(init_args(cash_link_bump).try_to_vec().unwrap());
+    Instruction::new_with_bytes(
+        *caller_id,
+        &data,
+        vec![
+            AccountMeta::new(rewards, false),
+            AccountMeta::new_readonly(*payer, true),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(cash_link, false),
+            AccountMeta::new_readonly(*pass_key, false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+            AccountMeta::new_readonly(find_config_program_address(&cash::id()).0, false),
+            AccountMeta::new_readonly(cash::id(), false),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_init_native_cash_link_owned_by_pda() {
+    let caller_id = Pubkey::new_unique();
+    let (mut banks_client, payer, recent_blockhash) = program_test(&caller_id, false).start().await;
+    let pass_key = Keypair::new().pubkey();
+    let transaction = Transaction::new_signed_with_payer(
+        &[init_instruction(&caller_id, &payer.pubkey(), &pass_key)],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash,
+    );
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    let (rewards, _) = find_rewards_address(&caller_id);
+    let (cash_link_key, _) = find_cash_link_program_address(&cash::id(), pass_key);
+    let cash_link_account = banks_client.get_account(cash_link_key).await.unwrap().unwrap();
+    let cash_link = CashLink::unpack(&cash_link_account.data).unwrap();
+    assert_eq!(cash_link.owner, rewards);
+    assert_eq!(cash_link.amount, AMOUNT);
+    assert_eq!(cash_link.state, CashLinkState::Initialized);
+    assert_eq!(
+        cash_link_account.lamports,
+        Rent::default().minimum_balance(CashLink::LEN) + AMOUNT
+    );
+    let rewards_account = banks_client.get_account(rewards).await.unwrap().unwrap();
+    assert_eq!(rewards_account.lamports, REWARDS_LAMPORTS - AMOUNT);
+}
+
+#[tokio::test]
+async fn test_init_through_cpi_rejected_while_paused() {
+    let caller_id = Pubkey::new_unique();
+    let (mut banks_client, payer, recent_blockhash) = program_test(&caller_id, true).start().await;
+    let transaction = Transaction::new_signed_with_payer(
+        &[init_instruction(&caller_id, &payer.pubkey(), &Keypair::new().pubkey())],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash,
+    );
+    let err = banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CashError::ProgramPaused as u32)
+        )
+    );
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use cash::{
+    find_cash_link_program_address,
+    instruction::{batch_init_cash_links, BatchInitCashLinksArgs, InitCashLinkArgs},
+    state::cashlink::{CashLink, CashLinkState},
+};
+use common::{create_mint, create_vaults, process, program_test, token_balance, MINTED};
+use solana_program::{program_pack::Pack, pubkey::Pubkey, rent::Rent};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::{Keypair, Signer};
+
+const AMOUNT: u64 = 1_000;
+const NUM_LINKS: usize = 3;
+
+/// Initialize `NUM_LINKS` links of `AMOUNT` owned by the payer
+async fn batch_init(context: &mut ProgramTestContext, mint: Option<&Pubkey>) -> Vec<Pubkey> {
+    let payer = context.payer.pubkey();
+    let links: Vec<_> = (0..NUM_LINKS)
+        .map(|_| {
+            let pass_key = Keypair::new().pubkey();
+            let (cash_link, bump) = find_cash_link_program_address(&cash::id(), pass_key);
+            (cash_link, pass_key, bump)
+        })
+        .collect();
+    if let Some(mint) = mint {
+        for (cash_link, ..) in &links {
+            create_vaults(context, cash_link, &[*mint]).await;
+        }
+    }
+    let instruction = batch_init_cash_links(
+        &cash::id(),
+        &payer,
+        &payer,
+        &payer,
+        &links
+            .iter()
+            .map(|(cash_link, pass_key, _)| (*cash_link, *pass_key))
+            .collect::<Vec<_>>(),
+        mint,
+        BatchInitCashLinksArgs {
+            args: InitCashLinkArgs {
+                amount: AMOUNT,
+                max_num_redemptions: 1,
+                num_days_to_expire: 1,
+                ..InitCashLinkArgs::default()
+            },
+            cash_link_bumps: links.iter().map(|(.., bump)| *bump).collect(),
+        },
+    );
+    process(context, &[instruction], &[]).await.unwrap();
+    links.into_iter().map(|(cash_link, ..)| cash_link).collect()
+}
+
+#[tokio::test]
+async fn test_batch_splits_a_single_lamport_deposit() {
+    let mut context = program_test().start_with_context().await;
+    for cash_link in batch_init(&mut context, None).await {
+        let account = context.banks_client.get_account(cash_link).await.unwrap().unwrap();
+        assert_eq!(account.lamports, Rent::default().minimum_balance(CashLink::LEN) + AMOUNT);
+        let cash_link = CashLink::unpack(&account.data).unwrap();
+        assert_eq!(cash_link.state, CashLinkState::Initialized);
+        assert_eq!(cash_link.remaining_amount, AMOUNT);
+    }
+}
+
+#[tokio::test]
+async fn test_batch_splits_a_single_token_deposit() {
+    let mut context = program_test().start_with_context().await;
+    let mint = create_mint(&mut context, &[]).await;
+    for cash_link in batch_init(&mut context, Some(&mint)).await {
+        assert_eq!(token_balance(&mut context, &cash_link, &mint).await, AMOUNT);
+    }
+    let payer = context.payer.pubkey();
+    assert_eq!(
+        token_balance(&mut context, &payer, &mint).await,
+        MINTED - NUM_LINKS as u64 * AMOUNT
+    );
+}
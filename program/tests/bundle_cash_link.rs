@@ -0,0 +1,211 @@
+mod common;
+
+use cash::{
+    find_cash_link_program_address, find_cash_link_redemption_program_address,
+    instruction::{
+        init_cash_link, redeem_cash_link, BundleAssetArgs, InitCashLinkArgs, InitCashRedemptionArgs,
+    },
+    state::cashlink::{CashLink, CashLinkState, DistributionType},
+};
+use common::{
+    add_wallet, cash_link, cancel_instruction, create_mint, create_vaults, expire, init_link,
+    lamports, process, program_test, redeem_instruction, token_balance, TestLink, MINTED,
+};
+use solana_program::{program_pack::Pack, pubkey::Pubkey, rent::Rent};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::{Keypair, Signer};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::native_mint;
+
+const AMOUNT: u64 = 1_000;
+const MIN_AMOUNT: u64 = 600;
+const BUNDLE_LAMPORTS: u64 = 100_000;
+const BUNDLE_TOKENS: u64 = 100;
+
+/// A random link whose first redemption always leaves less than the minimum
+/// amount for the second, settling the link early
+#[tokio::test]
+async fn test_early_settle_refunds_bundle_leftover() {
+    let mut program_test = program_test();
+    let wallet = Keypair::new();
+    add_wallet(&mut program_test, wallet.pubkey(), 1_000_000_000);
+    let mut context = program_test.start_with_context().await;
+    context.warp_to_slot(4).unwrap();
+    let payer = context.payer.pubkey();
+    let mint = create_mint(&mut context, &[wallet.pubkey()]).await;
+    let bundle_mint = create_mint(&mut context, &[wallet.pubkey()]).await;
+    let bundle_mints = [native_mint::id(), bundle_mint];
+
+    let pass_key = Keypair::new();
+    let (cash_link, cash_link_bump) = find_cash_link_program_address(&cash::id(), pass_key.pubkey());
+    create_vaults(&mut context, &cash_link, &[mint, bundle_mint]).await;
+    let instruction = init_cash_link(
+        &cash::id(),
+        &payer,
+        &payer,
+        &payer,
+        &cash_link,
+        &pass_key.pubkey(),
+        Some(&mint),
+        InitCashLinkArgs {
+            amount: AMOUNT,
+            cash_link_bump,
+            distribution_type: DistributionType::Random,
+            max_num_redemptions: 2,
+            min_amount: Some(MIN_AMOUNT),
+            num_days_to_expire: 1,
+            bundle: Some(vec![
+                BundleAssetArgs {
+                    mint: native_mint::id(),
+                    amount: BUNDLE_LAMPORTS,
+                },
+                BundleAssetArgs {
+                    mint: bundle_mint,
+                    amount: BUNDLE_TOKENS,
+                },
+            ]),
+            ..InitCashLinkArgs::default()
+        },
+    );
+    process(&mut context, &[instruction], &[]).await.unwrap();
+
+    let wallet_lamports = context.banks_client.get_balance(wallet.pubkey()).await.unwrap();
+    let (redemption, redemption_bump) =
+        find_cash_link_redemption_program_address(&cash::id(), &cash_link, &wallet.pubkey());
+    let instruction = redeem_cash_link(
+        &cash::id(),
+        &payer,
+        &wallet.pubkey(),
+        &get_associated_token_address(&wallet.pubkey(), &mint),
+        &get_associated_token_address(&payer, &mint),
+        Some(&get_associated_token_address(&cash_link, &mint)),
+        &cash_link,
+        &pass_key.pubkey(),
+        &redemption,
+        &get_associated_token_address(&payer, &mint),
+        Some(&payer),
+        &payer,
+        None,
+        &mint,
+        None,
+        &bundle_mints,
+        InitCashRedemptionArgs {
+            redemption_bump,
+            cash_link_bump,
+            fingerprint: None,
+            fingerprint_bump: None,
+            allowlist_proof: None,
+            allowlist_amount: None,
+            claim_expires_at: None,
+            secret: None,
+            recipient: None,
+        },
+    );
+    process(&mut context, &[instruction], &[&wallet, &pass_key]).await.unwrap();
+
+    let redeemed = token_balance(&mut context, &wallet.pubkey(), &mint).await;
+    assert!((MIN_AMOUNT..AMOUNT).contains(&redeemed));
+    assert_eq!(token_balance(&mut context, &payer, &mint).await, MINTED - redeemed);
+    let account = context.banks_client.get_account(cash_link).await.unwrap().unwrap();
+    let cash_link = CashLink::unpack(&account.data).unwrap();
+    assert_eq!(cash_link.state, CashLinkState::Redeemed);
+    assert!(cash_link.bundle.iter().all(|asset| asset.remaining_amount == 0));
+    assert_eq!(account.lamports, Rent::default().minimum_balance(CashLink::LEN));
+
+    // the wallet gets the share of the bundle matching its share of the amount
+    let lamport_share = BUNDLE_LAMPORTS * redeemed / AMOUNT;
+    let token_share = BUNDLE_TOKENS * redeemed / AMOUNT;
+    assert_eq!(
+        context.banks_client.get_balance(wallet.pubkey()).await.unwrap(),
+        wallet_lamports + lamport_share
+    );
+    assert_eq!(token_balance(&mut context, &wallet.pubkey(), &bundle_mint).await, token_share);
+    // and the owner what is left of it
+    assert_eq!(
+        token_balance(&mut context, &payer, &bundle_mint).await,
+        MINTED - token_share
+    );
+}
+
+const FEE_TO_REDEEM: u64 = 5_000;
+
+/// A token link paying its fee in SOL, so holding a fee deposit alongside a
+/// native and a token bundle asset
+async fn init_fee_deposit_link(
+    context: &mut ProgramTestContext,
+    wallets: &[Pubkey],
+) -> (TestLink, Pubkey, Pubkey) {
+    let mint = create_mint(context, wallets).await;
+    let bundle_mint = create_mint(context, wallets).await;
+    let link = init_link(
+        context,
+        Some(mint),
+        InitCashLinkArgs {
+            amount: AMOUNT,
+            fee_to_redeem: FEE_TO_REDEEM,
+            max_num_redemptions: 1,
+            num_days_to_expire: 1,
+            fees_in_sol: Some(true),
+            bundle: Some(vec![
+                BundleAssetArgs {
+                    mint: native_mint::id(),
+                    amount: BUNDLE_LAMPORTS,
+                },
+                BundleAssetArgs {
+                    mint: bundle_mint,
+                    amount: BUNDLE_TOKENS,
+                },
+            ]),
+            ..InitCashLinkArgs::default()
+        },
+    )
+    .await
+    .unwrap();
+    (link, mint, bundle_mint)
+}
+
+#[tokio::test]
+async fn test_redeem_bundle_link_with_fee_deposit() {
+    let mut program_test = program_test();
+    let wallet = Keypair::new();
+    let fee_wallet = Pubkey::new_unique();
+    add_wallet(&mut program_test, wallet.pubkey(), 1_000_000_000);
+    add_wallet(&mut program_test, fee_wallet, 1_000_000_000);
+    let mut context = program_test.start_with_context().await;
+    let payer = context.payer.pubkey();
+    let (link, mint, bundle_mint) = init_fee_deposit_link(&mut context, &[wallet.pubkey()]).await;
+
+    let instruction = redeem_instruction(
+        &payer,
+        &link,
+        &wallet.pubkey(),
+        &fee_wallet,
+        InitCashRedemptionArgs::default(),
+    );
+    process(&mut context, &[instruction], &[&wallet, &link.pass_key]).await.unwrap();
+
+    assert_eq!(token_balance(&mut context, &wallet.pubkey(), &mint).await, AMOUNT);
+    assert_eq!(token_balance(&mut context, &wallet.pubkey(), &bundle_mint).await, BUNDLE_TOKENS);
+    assert_eq!(lamports(&mut context, &wallet.pubkey()).await, 1_000_000_000 + BUNDLE_LAMPORTS);
+    assert_eq!(lamports(&mut context, &fee_wallet).await, 1_000_000_000 + FEE_TO_REDEEM);
+    let (cash_link, lamports) = cash_link(&mut context, &link.cash_link).await;
+    assert_eq!(cash_link.state, CashLinkState::Redeemed);
+    assert_eq!(lamports, Rent::default().minimum_balance(CashLink::LEN));
+}
+
+#[tokio::test]
+async fn test_cancel_bundle_link_with_fee_deposit() {
+    let mut context = program_test().start_with_context().await;
+    let payer = context.payer.pubkey();
+    let (link, mint, bundle_mint) = init_fee_deposit_link(&mut context, &[]).await;
+    expire(&mut context).await;
+
+    let instruction = cancel_instruction(&payer, &link);
+    process(&mut context, &[instruction], &[]).await.unwrap();
+
+    assert_eq!(token_balance(&mut context, &payer, &mint).await, MINTED);
+    assert_eq!(token_balance(&mut context, &payer, &bundle_mint).await, MINTED);
+    let (cash_link, lamports) = cash_link(&mut context, &link.cash_link).await;
+    assert_eq!(cash_link.state, CashLinkState::Cancelled);
+    assert_eq!(lamports, Rent::default().minimum_balance(CashLink::LEN));
+}
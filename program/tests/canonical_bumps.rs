@@ -0,0 +1,142 @@
+mod common;
+
+use cash::{
+    error::CashError,
+    find_cash_link_redemption_program_address, find_fingerprint_program_address,
+    instruction::{redeem_cash_link, InitCashLinkArgs, InitCashRedemptionArgs},
+    state::{FINGERPRINT_PREFIX, REDEMPTION_PREFIX},
+};
+use common::{add_wallet, init_link, process, program_test, TestLink};
+use solana_program::{instruction::InstructionError, pubkey::Pubkey};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+const AMOUNT: u64 = 1_000_000_000;
+const FINGERPRINT: &str = "3yZe7d";
+
+/// The highest bump below the canonical one deriving a program address
+fn non_canonical_address(seeds: &[&[u8]]) -> (Pubkey, u8) {
+    let (_, canonical) = Pubkey::find_program_address(seeds, &cash::id());
+    (0..canonical)
+        .rev()
+        .find_map(|bump| {
+            let bump = [bump];
+            let mut seeds = seeds.to_vec();
+            seeds.push(&bump);
+            Pubkey::create_program_address(&seeds, &cash::id())
+                .ok()
+                .map(|address| (address, bump[0]))
+        })
+        .unwrap()
+}
+
+async fn setup() -> (ProgramTestContext, TestLink, Pubkey) {
+    let mut program_test = program_test();
+    let fee_wallet = Pubkey::new_unique();
+    add_wallet(&mut program_test, fee_wallet, 1_000_000_000);
+    let mut context = program_test.start_with_context().await;
+    let link = init_link(
+        &mut context,
+        None,
+        InitCashLinkArgs {
+            amount: AMOUNT,
+            max_num_redemptions: 2,
+            num_days_to_expire: 1,
+            fingerprint_enabled: Some(true),
+            ..InitCashLinkArgs::default()
+        },
+    )
+    .await
+    .unwrap();
+    (context, link, fee_wallet)
+}
+
+/// Redeem the native `link` for `wallet` through the given redemption and
+/// fingerprint accounts
+async fn redeem(
+    context: &mut ProgramTestContext,
+    link: &TestLink,
+    fee_wallet: &Pubkey,
+    wallet: &Keypair,
+    (redemption, redemption_bump): (Pubkey, u8),
+    (fingerprint, fingerprint_bump): (Pubkey, u8),
+) -> Result<(), TransactionError> {
+    let payer = context.payer.pubkey();
+    let instruction = redeem_cash_link(
+        &cash::id(),
+        &payer,
+        &wallet.pubkey(),
+        &wallet.pubkey(),
+        fee_wallet,
+        None,
+        &link.cash_link,
+        &link.pass_key.pubkey(),
+        &redemption,
+        &payer,
+        None,
+        &payer,
+        Some(&fingerprint),
+        &Pubkey::default(),
+        None,
+        &[],
+        InitCashRedemptionArgs {
+            redemption_bump,
+            cash_link_bump: link.cash_link_bump,
+            fingerprint: Some(FINGERPRINT.to_string()),
+            fingerprint_bump: Some(fingerprint_bump),
+            ..InitCashRedemptionArgs::default()
+        },
+    );
+    process(context, &[instruction], &[wallet, &link.pass_key]).await
+}
+
+fn custom(error: CashError) -> TransactionError {
+    TransactionError::InstructionError(0, InstructionError::Custom(error as u32))
+}
+
+#[tokio::test]
+async fn test_redeem_rejects_a_non_canonical_redemption() {
+    let (mut context, link, fee_wallet) = setup().await;
+    let wallet = Keypair::new();
+    let fingerprint = bs58::decode(FINGERPRINT).into_vec().unwrap();
+    let fingerprint_address =
+        find_fingerprint_program_address(&cash::id(), &link.cash_link, &fingerprint);
+    let redemption = non_canonical_address(&[
+        REDEMPTION_PREFIX.as_bytes(),
+        link.cash_link.as_ref(),
+        wallet.pubkey().as_ref(),
+    ]);
+
+    let err = redeem(&mut context, &link, &fee_wallet, &wallet, redemption, fingerprint_address)
+        .await
+        .unwrap_err();
+    assert_eq!(err, custom(CashError::InvalidRedemption));
+
+    let redemption =
+        find_cash_link_redemption_program_address(&cash::id(), &link.cash_link, &wallet.pubkey());
+    redeem(&mut context, &link, &fee_wallet, &wallet, redemption, fingerprint_address)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_redeem_rejects_a_non_canonical_fingerprint() {
+    let (mut context, link, fee_wallet) = setup().await;
+    let wallet = Keypair::new();
+    let redemption =
+        find_cash_link_redemption_program_address(&cash::id(), &link.cash_link, &wallet.pubkey());
+    let fingerprint = bs58::decode(FINGERPRINT).into_vec().unwrap();
+    let fingerprint_address = non_canonical_address(&[
+        FINGERPRINT_PREFIX.as_bytes(),
+        link.cash_link.as_ref(),
+        &fingerprint,
+    ]);
+
+    let err = redeem(&mut context, &link, &fee_wallet, &wallet, redemption, fingerprint_address)
+        .await
+        .unwrap_err();
+    assert_eq!(err, custom(CashError::InvalidFingerprint));
+}
@@ -0,0 +1,327 @@
+// every test crate includes this module and uses only some of it
+#![allow(dead_code)]
+
+use cash::{
+    find_cash_link_program_address, find_cash_link_redemption_program_address,
+    find_config_program_address,
+    instruction::{
+        cancel_cash_link, init_cash_link, redeem_cash_link, refund_cash_link,
+        CancelCashRedemptionArgs, InitCashLinkArgs, InitCashRedemptionArgs, RefundArgs,
+    },
+    processor::Processor,
+    state::{cashlink::CashLink, config::Config, AccountType},
+};
+use solana_program::{
+    clock::Clock, instruction::Instruction, program_pack::Pack, pubkey::Pubkey, rent::Rent,
+    system_instruction,
+};
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use spl_associated_token_account::{
+    get_associated_token_address, instruction::create_associated_token_account,
+};
+use spl_token::{
+    native_mint,
+    state::{Account as TokenAccount, Mint},
+};
+
+/// Tokens minted to the payer by `create_mint`
+pub const MINTED: u64 = 10_000;
+
+/// The cash program with an unpaused config
+pub fn program_test() -> ProgramTest {
+    let mut program_test = ProgramTest::new("cash", cash::id(), processor!(Processor::process));
+    let mut config = vec![0; Config::LEN];
+    Config {
+        account_type: AccountType::Config,
+        admin: Pubkey::new_unique(),
+        paused: false,
+    }
+    .pack_into_slice(&mut config);
+    program_test.add_account(
+        find_config_program_address(&cash::id()).0,
+        Account {
+            lamports: Rent::default().minimum_balance(Config::LEN),
+            data: config,
+            owner: cash::id(),
+            ..Account::default()
+        },
+    );
+    program_test
+}
+
+/// Add a system account holding `lamports`
+pub fn add_wallet(program_test: &mut ProgramTest, wallet: Pubkey, lamports: u64) {
+    program_test.add_account(
+        wallet,
+        Account {
+            lamports,
+            ..Account::default()
+        },
+    );
+}
+
+/// Process `instructions` paid and signed by the payer and `signers`
+pub async fn process(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), TransactionError> {
+    let mut signers = signers.to_vec();
+    signers.insert(0, &context.payer);
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &signers,
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .map_err(|err| err.unwrap())
+}
+
+/// Create a mint with `MINTED` tokens in the payer's associated token account
+/// and empty associated token accounts for `wallets`
+pub async fn create_mint(context: &mut ProgramTestContext, wallets: &[Pubkey]) -> Pubkey {
+    create_mint_with_supply(context, wallets, MINTED).await
+}
+
+/// Create a mint of `supply` tokens held by the payer's associated token
+/// account and empty associated token accounts for `wallets`
+pub async fn create_mint_with_supply(
+    context: &mut ProgramTestContext,
+    wallets: &[Pubkey],
+    supply: u64,
+) -> Pubkey {
+    let payer = context.payer.pubkey();
+    let mint = Keypair::new();
+    let mut instructions = vec![
+        system_instruction::create_account(
+            &payer,
+            &mint.pubkey(),
+            Rent::default().minimum_balance(Mint::LEN),
+            Mint::LEN as u64,
+            &spl_token::id(),
+        ),
+        spl_token::instruction::initialize_mint(&spl_token::id(), &mint.pubkey(), &payer, None, 0)
+            .unwrap(),
+    ];
+    for wallet in [payer].iter().chain(wallets) {
+        instructions.push(create_associated_token_account(
+            &payer,
+            wallet,
+            &mint.pubkey(),
+            &spl_token::id(),
+        ));
+    }
+    instructions.push(
+        spl_token::instruction::mint_to(
+            &spl_token::id(),
+            &mint.pubkey(),
+            &get_associated_token_address(&payer, &mint.pubkey()),
+            &payer,
+            &[],
+            supply,
+        )
+        .unwrap(),
+    );
+    process(context, &instructions, &[&mint]).await.unwrap();
+    mint.pubkey()
+}
+
+/// Create the vaults of `cash_link` for `mints`. program-test cannot resolve
+/// the programs the associated token account program invokes when called from
+/// the cash program, so tests create them up front
+pub async fn create_vaults(context: &mut ProgramTestContext, cash_link: &Pubkey, mints: &[Pubkey]) {
+    let payer = context.payer.pubkey();
+    let vaults: Vec<_> = mints
+        .iter()
+        .map(|mint| create_associated_token_account(&payer, cash_link, mint, &spl_token::id()))
+        .collect();
+    process(context, &vaults, &[]).await.unwrap();
+}
+
+/// The balance of the associated token account of `wallet` for `mint`
+pub async fn token_balance(context: &mut ProgramTestContext, wallet: &Pubkey, mint: &Pubkey) -> u64 {
+    let address = get_associated_token_address(wallet, mint);
+    let account = context.banks_client.get_account(address).await.unwrap().unwrap();
+    TokenAccount::unpack(&account.data).unwrap().amount
+}
+
+/// The balance of a system account
+pub async fn lamports(context: &mut ProgramTestContext, address: &Pubkey) -> u64 {
+    context.banks_client.get_balance(*address).await.unwrap()
+}
+
+/// Read a cash link and its lamports
+pub async fn cash_link(context: &mut ProgramTestContext, address: &Pubkey) -> (CashLink, u64) {
+    let account = context.banks_client.get_account(*address).await.unwrap().unwrap();
+    (CashLink::unpack(&account.data).unwrap(), account.lamports)
+}
+
+/// Move the clock `seconds` forward
+pub async fn advance_clock(context: &mut ProgramTestContext, seconds: i64) {
+    let mut clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp += seconds;
+    context.set_sysvar(&clock);
+}
+
+/// Move the clock past the expiry of links created with `num_days_to_expire: 1`
+pub async fn expire(context: &mut ProgramTestContext) {
+    advance_clock(context, 86_400 + 1).await;
+}
+
+/// A cash link initialized by the payer
+pub struct TestLink {
+    pub pass_key: Keypair,
+    pub cash_link: Pubkey,
+    pub cash_link_bump: u8,
+    pub mint: Option<Pubkey>,
+    pub fees_in_sol: bool,
+    pub bundle_mints: Vec<Pubkey>,
+}
+
+/// Initialize a cash link of `mint` owned and paid for by the payer
+pub async fn init_link(
+    context: &mut ProgramTestContext,
+    mint: Option<Pubkey>,
+    args: InitCashLinkArgs,
+) -> Result<TestLink, TransactionError> {
+    let payer = context.payer.pubkey();
+    let pass_key = Keypair::new();
+    let (cash_link, cash_link_bump) = find_cash_link_program_address(&cash::id(), pass_key.pubkey());
+    let bundle_mints: Vec<_> = args.bundle.iter().flatten().map(|asset| asset.mint).collect();
+    let vault_mints: Vec<_> = mint
+        .iter()
+        .chain(&bundle_mints)
+        .filter(|key| **key != native_mint::id())
+        .copied()
+        .collect();
+    if !vault_mints.is_empty() {
+        create_vaults(context, &cash_link, &vault_mints).await;
+    }
+    let fees_in_sol = args.fees_in_sol.unwrap_or_default() || args.nft.unwrap_or_default();
+    let instruction = init_cash_link(
+        &cash::id(),
+        &payer,
+        &payer,
+        &payer,
+        &cash_link,
+        &pass_key.pubkey(),
+        mint.as_ref(),
+        InitCashLinkArgs {
+            cash_link_bump,
+            ..args
+        },
+    );
+    process(context, &[instruction], &[]).await?;
+    Ok(TestLink {
+        pass_key,
+        cash_link,
+        cash_link_bump,
+        mint,
+        fees_in_sol,
+        bundle_mints,
+    })
+}
+
+/// Redeem `link` for `wallet`, refunding the payer and paying fees to
+/// `fee_wallet`, or its associated token account unless they are paid in SOL
+pub fn redeem_instruction(
+    payer: &Pubkey,
+    link: &TestLink,
+    wallet: &Pubkey,
+    fee_wallet: &Pubkey,
+    args: InitCashRedemptionArgs,
+) -> Instruction {
+    let (redemption, redemption_bump) =
+        find_cash_link_redemption_program_address(&cash::id(), &link.cash_link, wallet);
+    let recipient = args.recipient.unwrap_or(*wallet);
+    let (recipient_token, fee_token, vault, owner_token, mint) = match link.mint {
+        Some(mint) => (
+            get_associated_token_address(&recipient, &mint),
+            if link.fees_in_sol {
+                *fee_wallet
+            } else {
+                get_associated_token_address(fee_wallet, &mint)
+            },
+            Some(get_associated_token_address(&link.cash_link, &mint)),
+            get_associated_token_address(payer, &mint),
+            mint,
+        ),
+        None => (recipient, *fee_wallet, None, *payer, Pubkey::default()),
+    };
+    redeem_cash_link(
+        &cash::id(),
+        payer,
+        wallet,
+        &recipient_token,
+        &fee_token,
+        vault.as_ref(),
+        &link.cash_link,
+        &link.pass_key.pubkey(),
+        &redemption,
+        &owner_token,
+        link.mint.map(|_| payer),
+        payer,
+        None,
+        &mint,
+        None,
+        &link.bundle_mints,
+        InitCashRedemptionArgs {
+            redemption_bump,
+            cash_link_bump: link.cash_link_bump,
+            ..args
+        },
+    )
+}
+
+/// Cancel `link` once expired, refunding the payer
+pub fn cancel_instruction(payer: &Pubkey, link: &TestLink) -> Instruction {
+    let (owner_token, vault) = match link.mint {
+        Some(mint) => (
+            get_associated_token_address(payer, &mint),
+            Some(get_associated_token_address(&link.cash_link, &mint)),
+        ),
+        None => (*payer, None),
+    };
+    cancel_cash_link(
+        &cash::id(),
+        payer,
+        &link.cash_link,
+        &link.pass_key.pubkey(),
+        &owner_token,
+        vault.as_ref(),
+        payer,
+        payer,
+        None,
+        &link.bundle_mints,
+        CancelCashRedemptionArgs {
+            cash_link_bump: link.cash_link_bump,
+            reason: None,
+        },
+    )
+}
+
+/// Crank the refund of `link` once expired, refunding `owner`
+pub fn refund_instruction(cranker: &Pubkey, owner: &Pubkey, link: &TestLink) -> Instruction {
+    refund_cash_link(
+        &cash::id(),
+        cranker,
+        &link.cash_link,
+        &link.pass_key.pubkey(),
+        owner,
+        link.mint.as_ref(),
+        None,
+        &link.bundle_mints,
+        RefundArgs {
+            cash_link_bump: link.cash_link_bump,
+        },
+    )
+}
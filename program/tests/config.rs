@@ -0,0 +1,166 @@
+mod common;
+
+use cash::{
+    error::CashError,
+    find_cash_link_program_address, find_config_program_address,
+    instruction::{init_cash_link, set_config, InitCashLinkArgs, SetConfigArgs},
+    processor::Processor,
+    state::{
+        cashlink::{CashLink, CashLinkState},
+        config::Config,
+        AccountType,
+    },
+};
+use common::process;
+use solana_program::{
+    instruction::{Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+};
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+/// Initialize a native link owned by `payer`, passing `config` in place of the
+/// config address if set
+fn init(payer: &Pubkey, config: Option<Pubkey>) -> (Pubkey, Instruction) {
+    let pass_key = Keypair::new().pubkey();
+    let (cash_link, cash_link_bump) = find_cash_link_program_address(&cash::id(), pass_key);
+    let mut instruction = init_cash_link(
+        &cash::id(),
+        payer,
+        payer,
+        payer,
+        &cash_link,
+        &pass_key,
+        None,
+        InitCashLinkArgs {
+            amount: 1_000,
+            cash_link_bump,
+            max_num_redemptions: 1,
+            num_days_to_expire: 1,
+            ..InitCashLinkArgs::default()
+        },
+    );
+    if let Some(config) = config {
+        instruction.accounts.last_mut().unwrap().pubkey = config;
+    }
+    (cash_link, instruction)
+}
+
+#[tokio::test]
+async fn test_unset_config_does_not_pause() {
+    let program_test = ProgramTest::new("cash", cash::id(), processor!(Processor::process));
+    let mut context = program_test.start_with_context().await;
+    let (cash_link, instruction) = init(&context.payer.pubkey(), None);
+    process(&mut context, &[instruction], &[]).await.unwrap();
+
+    let account = context.banks_client.get_account(cash_link).await.unwrap().unwrap();
+    assert_eq!(CashLink::unpack(&account.data).unwrap().state, CashLinkState::Initialized);
+}
+
+#[tokio::test]
+async fn test_config_must_be_the_config_address() {
+    let program_test = ProgramTest::new("cash", cash::id(), processor!(Processor::process));
+    let mut context = program_test.start_with_context().await;
+    let (_, instruction) = init(&context.payer.pubkey(), Some(Pubkey::new_unique()));
+    assert_eq!(
+        process(&mut context, &[instruction], &[]).await.unwrap_err(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CashError::InvalidConfig as u32)
+        )
+    );
+}
+
+/// Start the program with a config administered by the returned keypair at
+/// `address`, the config address by default
+async fn with_admin(address: Option<Pubkey>) -> (ProgramTestContext, Keypair) {
+    let mut program_test = ProgramTest::new("cash", cash::id(), processor!(Processor::process));
+    let admin = Keypair::new();
+    let mut config = vec![0; Config::LEN];
+    Config {
+        account_type: AccountType::Config,
+        admin: admin.pubkey(),
+        paused: false,
+    }
+    .pack_into_slice(&mut config);
+    program_test.add_account(
+        address.unwrap_or_else(|| find_config_program_address(&cash::id()).0),
+        Account {
+            lamports: Rent::default().minimum_balance(Config::LEN),
+            data: config,
+            owner: cash::id(),
+            ..Account::default()
+        },
+    );
+    (program_test.start_with_context().await, admin)
+}
+
+/// Pause the program as `admin`
+fn pause(context: &ProgramTestContext, admin: &Keypair, config_bump: u8) -> Instruction {
+    set_config(
+        &cash::id(),
+        &admin.pubkey(),
+        &context.payer.pubkey(),
+        None,
+        SetConfigArgs {
+            config_bump,
+            admin: admin.pubkey(),
+            paused: true,
+        },
+    )
+}
+
+#[tokio::test]
+async fn test_admin_pauses_the_program() {
+    let (mut context, admin) = with_admin(None).await;
+    let (config, config_bump) = find_config_program_address(&cash::id());
+    let instruction = pause(&context, &admin, config_bump);
+    process(&mut context, &[instruction], &[&admin]).await.unwrap();
+
+    let account = context.banks_client.get_account(config).await.unwrap().unwrap();
+    assert!(Config::unpack(&account.data).unwrap().paused);
+    let (_, instruction) = init(&context.payer.pubkey(), None);
+    assert_eq!(
+        process(&mut context, &[instruction], &[]).await.unwrap_err(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CashError::ProgramPaused as u32)
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_set_config_rejects_a_non_canonical_bump() {
+    let (mut context, admin) = with_admin(None).await;
+    let (_, config_bump) = find_config_program_address(&cash::id());
+    let instruction = pause(&context, &admin, config_bump.wrapping_sub(1));
+    assert_eq!(
+        process(&mut context, &[instruction], &[&admin]).await.unwrap_err(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CashError::InvalidConfig as u32)
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_set_config_must_be_the_config_address() {
+    let decoy = Pubkey::new_unique();
+    let (mut context, admin) = with_admin(Some(decoy)).await;
+    let (_, config_bump) = find_config_program_address(&cash::id());
+    let mut instruction = pause(&context, &admin, config_bump);
+    instruction.accounts[1].pubkey = decoy;
+    assert_eq!(
+        process(&mut context, &[instruction], &[&admin]).await.unwrap_err(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CashError::InvalidConfig as u32)
+        )
+    );
+}
@@ -0,0 +1,102 @@
+mod common;
+
+use cash::{
+    error::CashError,
+    instruction::{InitCashLinkArgs, InitCashRedemptionArgs},
+};
+use common::{
+    add_wallet, advance_clock, init_link, lamports, process, program_test, redeem_instruction,
+    TestLink,
+};
+use solana_program::{instruction::InstructionError, pubkey::Pubkey};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+const SHARE: u64 = 1_000_000_000;
+const COOLDOWN: u64 = 60;
+
+async fn setup(args: InitCashLinkArgs) -> (ProgramTestContext, TestLink, Pubkey) {
+    let mut program_test = program_test();
+    let fee_wallet = Pubkey::new_unique();
+    add_wallet(&mut program_test, fee_wallet, 1_000_000_000);
+    let mut context = program_test.start_with_context().await;
+    let link = init_link(
+        &mut context,
+        None,
+        InitCashLinkArgs {
+            amount: 2 * SHARE,
+            max_num_redemptions: 2,
+            num_days_to_expire: 1,
+            ..args
+        },
+    )
+    .await
+    .unwrap();
+    (context, link, fee_wallet)
+}
+
+async fn redeem(
+    context: &mut ProgramTestContext,
+    link: &TestLink,
+    fee_wallet: &Pubkey,
+    wallet: &Keypair,
+) -> Result<(), TransactionError> {
+    let payer = context.payer.pubkey();
+    let instruction = redeem_instruction(
+        &payer,
+        link,
+        &wallet.pubkey(),
+        fee_wallet,
+        InitCashRedemptionArgs::default(),
+    );
+    // retried claims must not be deduplicated as the transaction that failed
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    process(context, &[instruction], &[wallet, &link.pass_key]).await
+}
+
+fn custom(error: CashError) -> TransactionError {
+    TransactionError::InstructionError(0, InstructionError::Custom(error as u32))
+}
+
+#[tokio::test]
+async fn test_link_cooldown_spaces_redemptions() {
+    let (mut context, link, fee_wallet) = setup(InitCashLinkArgs {
+        cooldown_seconds: Some(COOLDOWN),
+        ..InitCashLinkArgs::default()
+    })
+    .await;
+    let (first, second) = (Keypair::new(), Keypair::new());
+    redeem(&mut context, &link, &fee_wallet, &first).await.unwrap();
+    assert_eq!(
+        redeem(&mut context, &link, &fee_wallet, &second).await.unwrap_err(),
+        custom(CashError::CooldownActive)
+    );
+
+    advance_clock(&mut context, COOLDOWN as i64).await;
+    redeem(&mut context, &link, &fee_wallet, &second).await.unwrap();
+    assert_eq!(lamports(&mut context, &first.pubkey()).await, SHARE);
+    assert_eq!(lamports(&mut context, &second.pubkey()).await, SHARE);
+}
+
+#[tokio::test]
+async fn test_wallet_cooldown_spaces_claims() {
+    let (mut context, link, fee_wallet) = setup(InitCashLinkArgs {
+        max_claims_per_wallet: Some(2),
+        wallet_cooldown_seconds: Some(COOLDOWN),
+        ..InitCashLinkArgs::default()
+    })
+    .await;
+    let wallet = Keypair::new();
+    redeem(&mut context, &link, &fee_wallet, &wallet).await.unwrap();
+    assert_eq!(
+        redeem(&mut context, &link, &fee_wallet, &wallet).await.unwrap_err(),
+        custom(CashError::WalletCooldownActive)
+    );
+
+    advance_clock(&mut context, COOLDOWN as i64).await;
+    redeem(&mut context, &link, &fee_wallet, &wallet).await.unwrap();
+    assert_eq!(lamports(&mut context, &wallet.pubkey()).await, 2 * SHARE);
+}
@@ -0,0 +1,147 @@
+mod common;
+
+use cash::{
+    find_cash_link_redemption_program_address,
+    instruction::{distribute, DistributeArgs, InitCashLinkArgs},
+    state::{
+        cashlink::{CashLink, CashLinkState},
+        redemption::Redemption,
+    },
+};
+use common::{
+    add_wallet, cash_link, create_mint, init_link, lamports, process, program_test,
+    token_balance, TestLink,
+};
+use solana_program::{instruction::Instruction, program_pack::Pack, pubkey::Pubkey, rent::Rent};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::Signer;
+use spl_associated_token_account::get_associated_token_address;
+
+const AMOUNT: u64 = 2_000_000_000;
+const TOKEN_AMOUNT: u64 = 1_000;
+const FEE_TO_REDEEM: u64 = 10;
+const RELAYER_FEE: u64 = 100_000;
+const FEE_WALLET_LAMPORTS: u64 = 1_000_000_000;
+const SIGNATURE_FEE: u64 = 5_000;
+
+/// Distribute `link` to `wallets`, paying fees to `fee_wallet` and its
+/// associated token account for the link's mint
+fn distribute_instruction(
+    payer: &Pubkey,
+    link: &TestLink,
+    fee_wallet: &Pubkey,
+    wallets: &[Pubkey],
+) -> Instruction {
+    let redemptions: Vec<_> = wallets
+        .iter()
+        .map(|wallet| find_cash_link_redemption_program_address(&cash::id(), &link.cash_link, wallet))
+        .collect();
+    let (fee_token, owner_token) = match link.mint {
+        Some(mint) => (
+            get_associated_token_address(fee_wallet, &mint),
+            get_associated_token_address(payer, &mint),
+        ),
+        None => (*fee_wallet, *payer),
+    };
+    distribute(
+        &cash::id(),
+        payer,
+        &link.cash_link,
+        &link.pass_key.pubkey(),
+        &fee_token,
+        &owner_token,
+        link.mint.map(|_| payer),
+        payer,
+        link.mint.as_ref(),
+        &wallets
+            .iter()
+            .zip(&redemptions)
+            .map(|(wallet, (redemption, _))| (*wallet, *redemption))
+            .collect::<Vec<_>>(),
+        DistributeArgs {
+            cash_link_bump: link.cash_link_bump,
+            redemption_bumps: redemptions.iter().map(|(_, bump)| *bump).collect(),
+        },
+    )
+}
+
+async fn setup() -> (ProgramTestContext, Pubkey) {
+    let mut program_test = program_test();
+    let fee_wallet = Pubkey::new_unique();
+    add_wallet(&mut program_test, fee_wallet, FEE_WALLET_LAMPORTS);
+    (program_test.start_with_context().await, fee_wallet)
+}
+
+#[tokio::test]
+async fn test_distribute_pays_every_wallet_and_the_relayer() {
+    let (mut context, fee_wallet) = setup().await;
+    let payer = context.payer.pubkey();
+    let link = init_link(
+        &mut context,
+        None,
+        InitCashLinkArgs {
+            amount: AMOUNT,
+            fee_to_redeem: FEE_TO_REDEEM,
+            max_num_redemptions: 2,
+            num_days_to_expire: 1,
+            relayer_fee: Some(RELAYER_FEE),
+            ..InitCashLinkArgs::default()
+        },
+    )
+    .await
+    .unwrap();
+    let wallets = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let payer_lamports = lamports(&mut context, &payer).await;
+
+    let instruction = distribute_instruction(&payer, &link, &fee_wallet, &wallets);
+    process(&mut context, &[instruction], &[]).await.unwrap();
+
+    for wallet in &wallets {
+        assert_eq!(lamports(&mut context, wallet).await, AMOUNT / 2);
+    }
+    assert_eq!(
+        lamports(&mut context, &fee_wallet).await,
+        FEE_WALLET_LAMPORTS + 2 * FEE_TO_REDEEM
+    );
+    // the relayer is paid back more than the redemption accounts cost it
+    assert_eq!(
+        lamports(&mut context, &payer).await,
+        payer_lamports + 2 * RELAYER_FEE
+            - 2 * Rent::default().minimum_balance(Redemption::LEN)
+            - SIGNATURE_FEE
+    );
+    let (cash_link, lamports) = cash_link(&mut context, &link.cash_link).await;
+    assert_eq!(cash_link.state, CashLinkState::Redeemed);
+    assert_eq!(lamports, Rent::default().minimum_balance(CashLink::LEN));
+}
+
+#[tokio::test]
+async fn test_distribute_token_link() {
+    let (mut context, fee_wallet) = setup().await;
+    let payer = context.payer.pubkey();
+    let wallets = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let mint = create_mint(&mut context, &[wallets[0], wallets[1], fee_wallet]).await;
+    let link = init_link(
+        &mut context,
+        Some(mint),
+        InitCashLinkArgs {
+            amount: TOKEN_AMOUNT,
+            fee_to_redeem: FEE_TO_REDEEM,
+            max_num_redemptions: 2,
+            num_days_to_expire: 1,
+            ..InitCashLinkArgs::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    let instruction = distribute_instruction(&payer, &link, &fee_wallet, &wallets);
+    process(&mut context, &[instruction], &[]).await.unwrap();
+
+    for wallet in &wallets {
+        assert_eq!(token_balance(&mut context, wallet, &mint).await, TOKEN_AMOUNT / 2);
+    }
+    assert_eq!(token_balance(&mut context, &fee_wallet, &mint).await, 2 * FEE_TO_REDEEM);
+    let (cash_link, _) = cash_link(&mut context, &link.cash_link).await;
+    assert_eq!(cash_link.state, CashLinkState::Redeemed);
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use cash::{
+    instruction::{InitCashLinkArgs, InitCashRedemptionArgs},
+    state::cashlink::{CashLink, CashLinkState},
+};
+use common::{
+    add_wallet, cash_link, create_mint, create_mint_with_supply, init_link, lamports, process,
+    program_test, redeem_instruction, token_balance,
+};
+use solana_program::{program_pack::Pack, pubkey::Pubkey, rent::Rent};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::{Keypair, Signer};
+
+const AMOUNT: u64 = 1_000;
+const FEE_TO_REDEEM: u64 = 5_000;
+const FEE_WALLET_LAMPORTS: u64 = 1_000_000_000;
+
+async fn setup() -> (ProgramTestContext, Pubkey) {
+    let mut program_test = program_test();
+    let fee_wallet = Pubkey::new_unique();
+    add_wallet(&mut program_test, fee_wallet, FEE_WALLET_LAMPORTS);
+    (program_test.start_with_context().await, fee_wallet)
+}
+
+/// Redeem a token link of `amount` paying its fee in SOL to completion
+async fn redeem_to_completion(
+    context: &mut ProgramTestContext,
+    fee_wallet: &Pubkey,
+    wallet: &Keypair,
+    mint: Pubkey,
+    amount: u64,
+    nft: bool,
+) {
+    let payer = context.payer.pubkey();
+    let link = init_link(
+        context,
+        Some(mint),
+        InitCashLinkArgs {
+            amount,
+            fee_to_redeem: FEE_TO_REDEEM,
+            max_num_redemptions: 1,
+            num_days_to_expire: 1,
+            nft: Some(nft),
+            fees_in_sol: Some(!nft),
+            ..InitCashLinkArgs::default()
+        },
+    )
+    .await
+    .unwrap();
+    let (_, lamports_before) = cash_link(context, &link.cash_link).await;
+    assert_eq!(lamports_before, Rent::default().minimum_balance(CashLink::LEN) + FEE_TO_REDEEM);
+
+    let instruction = redeem_instruction(
+        &payer,
+        &link,
+        &wallet.pubkey(),
+        fee_wallet,
+        InitCashRedemptionArgs::default(),
+    );
+    process(context, &[instruction], &[wallet, &link.pass_key]).await.unwrap();
+
+    let (cash_link, lamports_after) = cash_link(context, &link.cash_link).await;
+    assert_eq!(cash_link.state, CashLinkState::Redeemed);
+    assert_eq!(lamports_after, Rent::default().minimum_balance(CashLink::LEN));
+    assert_eq!(lamports(context, fee_wallet).await, FEE_WALLET_LAMPORTS + FEE_TO_REDEEM);
+}
+
+#[tokio::test]
+async fn test_fees_in_sol_link_redeems_to_completion() {
+    let (mut context, fee_wallet) = setup().await;
+    let wallet = Keypair::new();
+    let mint = create_mint(&mut context, &[wallet.pubkey()]).await;
+    redeem_to_completion(&mut context, &fee_wallet, &wallet, mint, AMOUNT, false).await;
+    assert_eq!(token_balance(&mut context, &wallet.pubkey(), &mint).await, AMOUNT);
+}
+
+#[tokio::test]
+async fn test_nft_link_redeems_with_a_fee() {
+    let (mut context, fee_wallet) = setup().await;
+    let wallet = Keypair::new();
+    let mint = create_mint_with_supply(&mut context, &[wallet.pubkey()], 1).await;
+    redeem_to_completion(&mut context, &fee_wallet, &wallet, mint, 1, true).await;
+    assert_eq!(token_balance(&mut context, &wallet.pubkey(), &mint).await, 1);
+    let payer = context.payer.pubkey();
+    assert_eq!(token_balance(&mut context, &payer, &mint).await, 0);
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use cash::{
+    error::CashError,
+    instruction::{set_frozen, InitCashLinkArgs, InitCashRedemptionArgs, SetFrozenArgs},
+    state::cashlink::CashLinkState,
+};
+use common::{add_wallet, cash_link, init_link, lamports, process, program_test, redeem_instruction};
+use solana_program::{instruction::InstructionError, pubkey::Pubkey};
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+const AMOUNT: u64 = 1_000_000_000;
+
+#[tokio::test]
+async fn test_frozen_link_redeems_once_released() {
+    let mut program_test = program_test();
+    let fee_wallet = Pubkey::new_unique();
+    add_wallet(&mut program_test, fee_wallet, 1_000_000_000);
+    let mut context = program_test.start_with_context().await;
+    let payer = context.payer.pubkey();
+    let link = init_link(
+        &mut context,
+        None,
+        InitCashLinkArgs {
+            amount: AMOUNT,
+            max_num_redemptions: 1,
+            num_days_to_expire: 1,
+            ..InitCashLinkArgs::default()
+        },
+    )
+    .await
+    .unwrap();
+    let wallet = Keypair::new();
+    let freeze = |frozen| set_frozen(&cash::id(), &payer, &link.cash_link, SetFrozenArgs { frozen });
+
+    process(&mut context, &[freeze(true)], &[]).await.unwrap();
+    let (frozen, _) = cash_link(&mut context, &link.cash_link).await;
+    assert_eq!(frozen.state, CashLinkState::Frozen);
+    let instruction = redeem_instruction(
+        &payer,
+        &link,
+        &wallet.pubkey(),
+        &fee_wallet,
+        InitCashRedemptionArgs::default(),
+    );
+    assert_eq!(
+        process(&mut context, std::slice::from_ref(&instruction), &[&wallet, &link.pass_key])
+            .await
+            .unwrap_err(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CashError::AccountFrozen as u32)
+        )
+    );
+
+    process(&mut context, &[freeze(false), instruction], &[&wallet, &link.pass_key])
+        .await
+        .unwrap();
+    assert_eq!(lamports(&mut context, &wallet.pubkey()).await, AMOUNT);
+    let (cash_link, _) = cash_link(&mut context, &link.cash_link).await;
+    assert_eq!(cash_link.state, CashLinkState::Redeemed);
+}
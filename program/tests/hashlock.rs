@@ -0,0 +1,103 @@
+mod common;
+
+use cash::{
+    error::CashError,
+    find_cash_link_redemption_program_address, find_hashlock_cash_link_program_address,
+    instruction::{init_cash_link, redeem_cash_link, InitCashLinkArgs, InitCashRedemptionArgs},
+    state::cashlink::CashLinkState,
+};
+use common::{add_wallet, lamports, process, program_test};
+use solana_program::{
+    hash::hash,
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+};
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+const AMOUNT: u64 = 1_000_000_000;
+const SECRET: &str = "correct horse battery staple";
+
+/// Redeem the native hashlocked link at `cash_link` for `wallet` with `secret`
+fn redeem(
+    payer: &Pubkey,
+    cash_link: &Pubkey,
+    cash_link_bump: u8,
+    wallet: &Pubkey,
+    fee_wallet: &Pubkey,
+    secret: &str,
+) -> Instruction {
+    let (redemption, redemption_bump) =
+        find_cash_link_redemption_program_address(&cash::id(), cash_link, wallet);
+    redeem_cash_link(
+        &cash::id(),
+        payer,
+        wallet,
+        wallet,
+        fee_wallet,
+        None,
+        cash_link,
+        &Pubkey::new_from_array(hash(SECRET.as_bytes()).to_bytes()),
+        &redemption,
+        payer,
+        None,
+        payer,
+        None,
+        &Pubkey::default(),
+        None,
+        &[],
+        InitCashRedemptionArgs {
+            redemption_bump,
+            cash_link_bump,
+            secret: Some(secret.to_string()),
+            ..InitCashRedemptionArgs::default()
+        },
+    )
+}
+
+#[tokio::test]
+async fn test_hashlocked_link_redeems_with_its_secret() {
+    let mut program_test = program_test();
+    let fee_wallet = Pubkey::new_unique();
+    add_wallet(&mut program_test, fee_wallet, 1_000_000_000);
+    let mut context = program_test.start_with_context().await;
+    let payer = context.payer.pubkey();
+    let hashlock = hash(SECRET.as_bytes()).to_bytes();
+    let (cash_link, cash_link_bump) = find_hashlock_cash_link_program_address(&cash::id(), &hashlock);
+    let instruction = init_cash_link(
+        &cash::id(),
+        &payer,
+        &payer,
+        &payer,
+        &cash_link,
+        &Pubkey::new_from_array(hashlock),
+        None,
+        InitCashLinkArgs {
+            amount: AMOUNT,
+            cash_link_bump,
+            max_num_redemptions: 1,
+            num_days_to_expire: 1,
+            hashlock: Some(hashlock),
+            ..InitCashLinkArgs::default()
+        },
+    );
+    process(&mut context, &[instruction], &[]).await.unwrap();
+    let wallet = Keypair::new();
+
+    let instruction = redeem(&payer, &cash_link, cash_link_bump, &wallet.pubkey(), &fee_wallet, "guess");
+    assert_eq!(
+        process(&mut context, &[instruction], &[&wallet]).await.unwrap_err(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CashError::InvalidSecret as u32)
+        )
+    );
+
+    let instruction = redeem(&payer, &cash_link, cash_link_bump, &wallet.pubkey(), &fee_wallet, SECRET);
+    process(&mut context, &[instruction], &[&wallet]).await.unwrap();
+    assert_eq!(lamports(&mut context, &wallet.pubkey()).await, AMOUNT);
+    let (redeemed, _) = common::cash_link(&mut context, &cash_link).await;
+    assert_eq!(redeemed.state, CashLinkState::Redeemed);
+}
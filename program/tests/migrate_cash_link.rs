@@ -0,0 +1,156 @@
+mod common;
+
+use cash::{
+    error::CashError,
+    find_cash_link_program_address, find_cash_link_redemption_program_address,
+    instruction::{migrate_cash_link, InitCashRedemptionArgs},
+    state::{
+        cashlink::{CashLink, CashLinkState, LEGACY_CASH_LINK_DATA_SIZE},
+        AccountType, FLAG_ACCOUNT_SIZE,
+    },
+};
+use common::{add_wallet, cash_link, lamports, process, program_test, redeem_instruction, TestLink};
+use solana_program::{
+    instruction::InstructionError, program_pack::Pack, pubkey::Pubkey, rent::Rent,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+const SHARE: u64 = 1_000_000_000;
+
+/// Start with a native link of the payer in the legacy layout, of which
+/// `redeemed_wallet` claimed one of two shares under the legacy program
+async fn setup(redeemed_wallet: &Pubkey) -> (ProgramTestContext, TestLink, Pubkey) {
+    let mut program_test = program_test();
+    let fee_wallet = Pubkey::new_unique();
+    add_wallet(&mut program_test, fee_wallet, 1_000_000_000);
+    let mut context = program_test.start_with_context().await;
+    let payer = context.payer.pubkey();
+    let pass_key = Keypair::new();
+    let (cash_link, cash_link_bump) = find_cash_link_program_address(&cash::id(), pass_key.pubkey());
+    let mut data = vec![0; CashLink::LEN];
+    CashLink {
+        account_type: AccountType::CashLink,
+        authority: payer,
+        state: CashLinkState::Redeeming,
+        amount: 2 * SHARE,
+        remaining_amount: SHARE,
+        owner: payer,
+        expires_at: i64::MAX as u64,
+        total_redemptions: 1,
+        max_num_redemptions: 2,
+        pass_key: pass_key.pubkey(),
+        ..CashLink::default()
+    }
+    .pack_into_slice(&mut data);
+    data.truncate(LEGACY_CASH_LINK_DATA_SIZE);
+    context.set_account(
+        &cash_link,
+        &AccountSharedData::from(Account {
+            lamports: Rent::default().minimum_balance(LEGACY_CASH_LINK_DATA_SIZE) + SHARE,
+            data,
+            owner: cash::id(),
+            ..Account::default()
+        }),
+    );
+    let (redemption, _) =
+        find_cash_link_redemption_program_address(&cash::id(), &cash_link, redeemed_wallet);
+    context.set_account(
+        &redemption,
+        &AccountSharedData::from(Account {
+            lamports: Rent::default().minimum_balance(FLAG_ACCOUNT_SIZE),
+            data: vec![0; FLAG_ACCOUNT_SIZE],
+            owner: cash::id(),
+            ..Account::default()
+        }),
+    );
+    let link = TestLink {
+        pass_key,
+        cash_link,
+        cash_link_bump,
+        mint: None,
+        fees_in_sol: false,
+        bundle_mints: vec![],
+    };
+    (context, link, fee_wallet)
+}
+
+async fn redeem(
+    context: &mut ProgramTestContext,
+    link: &TestLink,
+    fee_wallet: &Pubkey,
+    wallet: &Keypair,
+) -> Result<(), TransactionError> {
+    let payer = context.payer.pubkey();
+    let instruction = redeem_instruction(
+        &payer,
+        link,
+        &wallet.pubkey(),
+        fee_wallet,
+        InitCashRedemptionArgs::default(),
+    );
+    process(context, &[instruction], &[wallet, &link.pass_key]).await
+}
+
+async fn migrate(context: &mut ProgramTestContext, link: &TestLink) -> Result<(), TransactionError> {
+    let instruction = migrate_cash_link(&cash::id(), &context.payer.pubkey(), &link.cash_link);
+    process(context, &[instruction], &[]).await
+}
+
+#[tokio::test]
+async fn test_legacy_link_redeems_once_migrated() {
+    let (mut context, link, fee_wallet) = setup(&Pubkey::new_unique()).await;
+    let wallet = Keypair::new();
+    assert_eq!(
+        redeem(&mut context, &link, &fee_wallet, &wallet).await.unwrap_err(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CashError::CashLinkNotMigrated as u32)
+        )
+    );
+
+    migrate(&mut context, &link).await.unwrap();
+    let (migrated, link_lamports) = cash_link(&mut context, &link.cash_link).await;
+    assert_eq!(migrated.remaining_amount, SHARE);
+    assert_eq!(migrated.max_claims_per_wallet, 1);
+    assert_eq!(link_lamports, Rent::default().minimum_balance(CashLink::LEN) + SHARE);
+
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    redeem(&mut context, &link, &fee_wallet, &wallet).await.unwrap();
+    assert_eq!(lamports(&mut context, &wallet.pubkey()).await, SHARE);
+    let (cash_link, link_lamports) = cash_link(&mut context, &link.cash_link).await;
+    assert_eq!(cash_link.state, CashLinkState::Redeemed);
+    assert_eq!(link_lamports, Rent::default().minimum_balance(CashLink::LEN));
+}
+
+#[tokio::test]
+async fn test_legacy_redemption_counts_as_a_claim() {
+    let redeemed_wallet = Keypair::new();
+    let (mut context, link, fee_wallet) = setup(&redeemed_wallet.pubkey()).await;
+    migrate(&mut context, &link).await.unwrap();
+    assert_eq!(
+        redeem(&mut context, &link, &fee_wallet, &redeemed_wallet).await.unwrap_err(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CashError::MaxClaimsPerWalletReached as u32)
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_migrate_rejects_a_migrated_link() {
+    let (mut context, link, _) = setup(&Pubkey::new_unique()).await;
+    migrate(&mut context, &link).await.unwrap();
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    assert_eq!(
+        migrate(&mut context, &link).await.unwrap_err(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CashError::CashLinkAlreadyMigrated as u32)
+        )
+    );
+}
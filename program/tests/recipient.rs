@@ -0,0 +1,66 @@
+mod common;
+
+use cash::instruction::{InitCashLinkArgs, InitCashRedemptionArgs};
+use common::{add_wallet, init_link, lamports, process, program_test, redeem_instruction};
+use solana_program::{instruction::InstructionError, pubkey::Pubkey};
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+const AMOUNT: u64 = 1_000_000_000;
+
+#[tokio::test]
+async fn test_redeem_to_recipient_requires_the_wallet_signature() {
+    let mut program_test = program_test();
+    let fee_wallet = Pubkey::new_unique();
+    add_wallet(&mut program_test, fee_wallet, 1_000_000_000);
+    let mut context = program_test.start_with_context().await;
+    let payer = context.payer.pubkey();
+    let link = init_link(
+        &mut context,
+        None,
+        InitCashLinkArgs {
+            amount: AMOUNT,
+            max_num_redemptions: 1,
+            num_days_to_expire: 1,
+            ..InitCashLinkArgs::default()
+        },
+    )
+    .await
+    .unwrap();
+    let wallet = Keypair::new();
+    let recipient = Pubkey::new_unique();
+
+    // the pass key alone can't redirect the wallet's share
+    let mut instruction = redeem_instruction(
+        &payer,
+        &link,
+        &wallet.pubkey(),
+        &fee_wallet,
+        InitCashRedemptionArgs {
+            recipient: Some(recipient),
+            ..InitCashRedemptionArgs::default()
+        },
+    );
+    instruction.accounts[1].is_signer = false;
+    let err = process(&mut context, &[instruction], &[&link.pass_key]).await.unwrap_err();
+    assert_eq!(
+        err,
+        TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature)
+    );
+
+    let instruction = redeem_instruction(
+        &payer,
+        &link,
+        &wallet.pubkey(),
+        &fee_wallet,
+        InitCashRedemptionArgs {
+            recipient: Some(recipient),
+            ..InitCashRedemptionArgs::default()
+        },
+    );
+    process(&mut context, &[instruction], &[&wallet, &link.pass_key]).await.unwrap();
+    assert_eq!(lamports(&mut context, &recipient).await, AMOUNT);
+    assert_eq!(lamports(&mut context, &wallet.pubkey()).await, 0);
+}
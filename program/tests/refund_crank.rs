@@ -0,0 +1,50 @@
+mod common;
+
+use cash::{
+    instruction::InitCashLinkArgs,
+    state::cashlink::{CashLink, CashLinkState},
+};
+use common::{
+    add_wallet, cash_link, create_mint, expire, init_link, lamports, process, program_test,
+    refund_instruction, token_balance, MINTED,
+};
+use solana_program::{program_pack::Pack, rent::Rent};
+use solana_sdk::signature::{Keypair, Signer};
+
+const AMOUNT: u64 = 1_000;
+const REFUND_BOUNTY: u64 = 50_000;
+const CRANKER_LAMPORTS: u64 = 1_000_000_000;
+
+#[tokio::test]
+async fn test_refund_crank_pays_the_bounty_of_a_token_link() {
+    let mut program_test = program_test();
+    let cranker = Keypair::new();
+    add_wallet(&mut program_test, cranker.pubkey(), CRANKER_LAMPORTS);
+    let mut context = program_test.start_with_context().await;
+    let payer = context.payer.pubkey();
+    let mint = create_mint(&mut context, &[]).await;
+    let link = init_link(
+        &mut context,
+        Some(mint),
+        InitCashLinkArgs {
+            amount: AMOUNT,
+            max_num_redemptions: 1,
+            num_days_to_expire: 1,
+            refund_bounty: Some(REFUND_BOUNTY),
+            ..InitCashLinkArgs::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(token_balance(&mut context, &payer, &mint).await, MINTED - AMOUNT);
+    expire(&mut context).await;
+
+    let instruction = refund_instruction(&cranker.pubkey(), &payer, &link);
+    process(&mut context, &[instruction], &[&cranker]).await.unwrap();
+
+    assert_eq!(lamports(&mut context, &cranker.pubkey()).await, CRANKER_LAMPORTS + REFUND_BOUNTY);
+    assert_eq!(token_balance(&mut context, &payer, &mint).await, MINTED);
+    let (cash_link, lamports) = cash_link(&mut context, &link.cash_link).await;
+    assert_eq!(cash_link.state, CashLinkState::Expired);
+    assert_eq!(lamports, Rent::default().minimum_balance(CashLink::LEN));
+}
@@ -0,0 +1,230 @@
+mod common;
+
+use cash::{
+    error::CashError,
+    find_cash_link_program_address, find_cash_link_redemption_program_address,
+    instruction::{
+        cancel_cash_link, init_cash_link, redeem_cash_link, CancelCashRedemptionArgs,
+        InitCashLinkArgs, InitCashRedemptionArgs,
+    },
+    state::cashlink::{CashLink, CashLinkState, DistributionType},
+};
+use common::{add_wallet, create_mint, create_vaults, process, program_test, token_balance, MINTED};
+use solana_program::{
+    clock::Clock, instruction::InstructionError, program_pack::Pack, pubkey::Pubkey, rent::Rent,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+const AMOUNT: u64 = 1_000_000_000;
+const TREASURY_LAMPORTS: u64 = 1_000_000_000;
+const TOKEN_AMOUNT: u64 = 1_000;
+const MIN_AMOUNT: u64 = 600;
+
+struct Link {
+    pass_key: Keypair,
+    cash_link: Pubkey,
+    cash_link_bump: u8,
+    treasury: Pubkey,
+}
+
+async fn setup() -> (ProgramTestContext, Link) {
+    let mut program_test = program_test();
+    let treasury = Pubkey::new_unique();
+    add_wallet(&mut program_test, treasury, TREASURY_LAMPORTS);
+    let mut context = program_test.start_with_context().await;
+    let payer = context.payer.pubkey();
+
+    let pass_key = Keypair::new();
+    let (cash_link, cash_link_bump) = find_cash_link_program_address(&cash::id(), pass_key.pubkey());
+    let instruction = init_cash_link(
+        &cash::id(),
+        &payer,
+        &payer,
+        &payer,
+        &cash_link,
+        &pass_key.pubkey(),
+        None,
+        InitCashLinkArgs {
+            amount: AMOUNT,
+            cash_link_bump,
+            max_num_redemptions: 1,
+            num_days_to_expire: 1,
+            refund_to: Some(treasury),
+            ..InitCashLinkArgs::default()
+        },
+    );
+    process(&mut context, &[instruction], &[]).await.unwrap();
+
+    let mut clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp += 86_400 + 1;
+    context.set_sysvar(&clock);
+    let link = Link {
+        pass_key,
+        cash_link,
+        cash_link_bump,
+        treasury,
+    };
+    (context, link)
+}
+
+async fn cancel(
+    context: &mut ProgramTestContext,
+    link: &Link,
+    refund_to: &Pubkey,
+) -> Result<(), TransactionError> {
+    let payer = context.payer.pubkey();
+    let instruction = cancel_cash_link(
+        &cash::id(),
+        &payer,
+        &link.cash_link,
+        &link.pass_key.pubkey(),
+        refund_to,
+        None,
+        &payer,
+        refund_to,
+        None,
+        &[],
+        CancelCashRedemptionArgs {
+            cash_link_bump: link.cash_link_bump,
+            reason: None,
+        },
+    );
+    process(context, &[instruction], &[]).await
+}
+
+#[tokio::test]
+async fn test_cancel_refunds_refund_to() {
+    let (mut context, link) = setup().await;
+    cancel(&mut context, &link, &link.treasury).await.unwrap();
+
+    let treasury = context.banks_client.get_account(link.treasury).await.unwrap().unwrap();
+    assert_eq!(treasury.lamports, TREASURY_LAMPORTS + AMOUNT);
+    let account = context.banks_client.get_account(link.cash_link).await.unwrap().unwrap();
+    assert_eq!(CashLink::unpack(&account.data).unwrap().state, CashLinkState::Cancelled);
+    assert_eq!(account.lamports, Rent::default().minimum_balance(CashLink::LEN));
+}
+
+#[tokio::test]
+async fn test_cancel_rejects_owner_when_refund_to_is_set() {
+    let (mut context, link) = setup().await;
+    let owner = context.payer.pubkey();
+    assert_eq!(
+        cancel(&mut context, &link, &owner).await.unwrap_err(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CashError::InvalidOwner as u32)
+        )
+    );
+}
+
+/// Initialize a random token link refunding `treasury`, whose first
+/// redemption always leaves less than the minimum amount for the second
+async fn init_token_link(
+    context: &mut ProgramTestContext,
+    mint: &Pubkey,
+    treasury: &Pubkey,
+) -> Result<(Keypair, Pubkey, u8), TransactionError> {
+    let payer = context.payer.pubkey();
+    let pass_key = Keypair::new();
+    let (cash_link, cash_link_bump) = find_cash_link_program_address(&cash::id(), pass_key.pubkey());
+    create_vaults(context, &cash_link, &[*mint]).await;
+    let instruction = init_cash_link(
+        &cash::id(),
+        &payer,
+        &payer,
+        &payer,
+        &cash_link,
+        &pass_key.pubkey(),
+        Some(mint),
+        InitCashLinkArgs {
+            amount: TOKEN_AMOUNT,
+            cash_link_bump,
+            distribution_type: DistributionType::Random,
+            max_num_redemptions: 2,
+            min_amount: Some(MIN_AMOUNT),
+            num_days_to_expire: 1,
+            refund_to: Some(*treasury),
+            ..InitCashLinkArgs::default()
+        },
+    );
+    process(context, &[instruction], &[]).await?;
+    Ok((pass_key, cash_link, cash_link_bump))
+}
+
+#[tokio::test]
+async fn test_last_redemption_refunds_leftover_to_refund_to() {
+    let mut context = program_test().start_with_context().await;
+    context.warp_to_slot(4).unwrap();
+    let payer = context.payer.pubkey();
+    let wallet = Keypair::new();
+    let treasury = Pubkey::new_unique();
+    let mint = create_mint(&mut context, &[wallet.pubkey(), treasury]).await;
+    let (pass_key, cash_link, cash_link_bump) =
+        init_token_link(&mut context, &mint, &treasury).await.unwrap();
+
+    let (redemption, redemption_bump) =
+        find_cash_link_redemption_program_address(&cash::id(), &cash_link, &wallet.pubkey());
+    let instruction = redeem_cash_link(
+        &cash::id(),
+        &payer,
+        &wallet.pubkey(),
+        &get_associated_token_address(&wallet.pubkey(), &mint),
+        &get_associated_token_address(&payer, &mint),
+        Some(&get_associated_token_address(&cash_link, &mint)),
+        &cash_link,
+        &pass_key.pubkey(),
+        &redemption,
+        &get_associated_token_address(&treasury, &mint),
+        None,
+        &payer,
+        None,
+        &mint,
+        None,
+        &[],
+        InitCashRedemptionArgs {
+            redemption_bump,
+            cash_link_bump,
+            fingerprint: None,
+            fingerprint_bump: None,
+            allowlist_proof: None,
+            allowlist_amount: None,
+            claim_expires_at: None,
+            secret: None,
+            recipient: None,
+        },
+    );
+    process(&mut context, &[instruction], &[&wallet, &pass_key]).await.unwrap();
+
+    let redeemed = token_balance(&mut context, &wallet.pubkey(), &mint).await;
+    assert!((MIN_AMOUNT..TOKEN_AMOUNT).contains(&redeemed));
+    assert_eq!(
+        token_balance(&mut context, &treasury, &mint).await,
+        TOKEN_AMOUNT - redeemed
+    );
+    assert_eq!(
+        token_balance(&mut context, &payer, &mint).await,
+        MINTED - TOKEN_AMOUNT
+    );
+    let account = context.banks_client.get_account(cash_link).await.unwrap().unwrap();
+    assert_eq!(CashLink::unpack(&account.data).unwrap().state, CashLinkState::Redeemed);
+}
+
+#[tokio::test]
+async fn test_init_rejects_refund_to_without_token_account() {
+    let mut context = program_test().start_with_context().await;
+    let mint = create_mint(&mut context, &[]).await;
+    assert_eq!(
+        init_token_link(&mut context, &mint, &Pubkey::new_unique())
+            .await
+            .unwrap_err(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CashError::InvalidRefundToken as u32)
+        )
+    );
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use cash::{
+    instruction::{InitCashLinkArgs, InitCashRedemptionArgs},
+    state::{
+        cashlink::{CashLink, CashLinkState},
+        redemption::Redemption,
+    },
+};
+use common::{add_wallet, cash_link, init_link, lamports, process, program_test, redeem_instruction};
+use solana_program::{program_pack::Pack, pubkey::Pubkey, rent::Rent};
+use solana_sdk::signature::{Keypair, Signer};
+
+const AMOUNT: u64 = 1_000_000_000;
+const FEE_TO_REDEEM: u64 = 10;
+const RELAYER_FEE: u64 = 100_000;
+const SIGNATURE_FEE: u64 = 5_000;
+
+#[tokio::test]
+async fn test_redeem_pays_the_relayer() {
+    let mut program_test = program_test();
+    let fee_wallet = Pubkey::new_unique();
+    add_wallet(&mut program_test, fee_wallet, 1_000_000_000);
+    let mut context = program_test.start_with_context().await;
+    let payer = context.payer.pubkey();
+    let link = init_link(
+        &mut context,
+        None,
+        InitCashLinkArgs {
+            amount: AMOUNT,
+            fee_to_redeem: FEE_TO_REDEEM,
+            max_num_redemptions: 1,
+            num_days_to_expire: 1,
+            relayer_fee: Some(RELAYER_FEE),
+            ..InitCashLinkArgs::default()
+        },
+    )
+    .await
+    .unwrap();
+    let wallet = Keypair::new();
+    let payer_lamports = lamports(&mut context, &payer).await;
+
+    let instruction = redeem_instruction(
+        &payer,
+        &link,
+        &wallet.pubkey(),
+        &fee_wallet,
+        InitCashRedemptionArgs::default(),
+    );
+    process(&mut context, &[instruction], &[&wallet, &link.pass_key]).await.unwrap();
+
+    assert_eq!(lamports(&mut context, &wallet.pubkey()).await, AMOUNT);
+    assert_eq!(lamports(&mut context, &fee_wallet).await, 1_000_000_000 + FEE_TO_REDEEM);
+    // the payer signs with the wallet and the pass key, and pays the redemption account
+    assert_eq!(
+        lamports(&mut context, &payer).await,
+        payer_lamports + RELAYER_FEE
+            - Rent::default().minimum_balance(Redemption::LEN)
+            - 3 * SIGNATURE_FEE
+    );
+    let (cash_link, lamports) = cash_link(&mut context, &link.cash_link).await;
+    assert_eq!(cash_link.state, CashLinkState::Redeemed);
+    assert_eq!(lamports, Rent::default().minimum_balance(CashLink::LEN));
+}
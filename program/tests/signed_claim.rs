@@ -0,0 +1,61 @@
+mod common;
+
+use cash::{
+    claim::claim_message,
+    instruction::{InitCashLinkArgs, InitCashRedemptionArgs},
+    state::cashlink::CashLinkState,
+};
+use common::{add_wallet, cash_link, init_link, lamports, process, program_test, redeem_instruction};
+use solana_program::{clock::Clock, pubkey::Pubkey};
+use solana_sdk::{
+    ed25519_instruction::new_ed25519_instruction,
+    signature::{Keypair, Signer},
+};
+
+const AMOUNT: u64 = 1_000_000_000;
+
+#[tokio::test]
+async fn test_claim_signed_by_the_pass_key_redeems() {
+    let mut program_test = program_test();
+    let fee_wallet = Pubkey::new_unique();
+    add_wallet(&mut program_test, fee_wallet, 1_000_000_000);
+    let mut context = program_test.start_with_context().await;
+    let payer = context.payer.pubkey();
+    let link = init_link(
+        &mut context,
+        None,
+        InitCashLinkArgs {
+            amount: AMOUNT,
+            max_num_redemptions: 1,
+            num_days_to_expire: 1,
+            ..InitCashLinkArgs::default()
+        },
+    )
+    .await
+    .unwrap();
+    let wallet = Keypair::new();
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    let claim_expires_at = clock.unix_timestamp as u64 + 60;
+
+    // the pass key signs the claim off-chain instead of the transaction
+    let pass_key = ed25519_dalek::Keypair::from_bytes(&link.pass_key.to_bytes()).unwrap();
+    let signature = new_ed25519_instruction(
+        &pass_key,
+        &claim_message(&link.cash_link, &wallet.pubkey(), 0, claim_expires_at),
+    );
+    let instruction = redeem_instruction(
+        &payer,
+        &link,
+        &wallet.pubkey(),
+        &fee_wallet,
+        InitCashRedemptionArgs {
+            claim_expires_at: Some(claim_expires_at),
+            ..InitCashRedemptionArgs::default()
+        },
+    );
+    process(&mut context, &[signature, instruction], &[&wallet]).await.unwrap();
+
+    assert_eq!(lamports(&mut context, &wallet.pubkey()).await, AMOUNT);
+    let (cash_link, _) = cash_link(&mut context, &link.cash_link).await;
+    assert_eq!(cash_link.state, CashLinkState::Redeemed);
+}
@@ -0,0 +1,243 @@
+mod common;
+
+use cash::{
+    find_cash_link_program_address, find_cash_link_redemption_program_address,
+    instruction::{
+        cancel_cash_link, init_cash_link, redeem_cash_link, refund_cash_link,
+        CancelCashRedemptionArgs, InitCashLinkArgs, InitCashRedemptionArgs, RefundArgs,
+    },
+    state::cashlink::{CashLink, CashLinkState},
+};
+use common::{add_wallet, program_test};
+use solana_program::{
+    clock::Clock,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    stake::{
+        self,
+        state::{Authorized, Lockup, StakeStateV2},
+    },
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+const STAKE_LAMPORTS: u64 = 2_000_000_000;
+const FIXED_FEE: u64 = 10_000_000;
+
+struct StakeLink {
+    stake: Pubkey,
+    pass_key: Keypair,
+    cash_link: Pubkey,
+    cash_link_bump: u8,
+    fee_wallet: Pubkey,
+}
+
+async fn setup() -> (ProgramTestContext, StakeLink) {
+    let mut program_test = program_test();
+    let fee_wallet = Pubkey::new_unique();
+    add_wallet(&mut program_test, fee_wallet, 1_000_000_000);
+    let mut context = program_test.start_with_context().await;
+    let payer = context.payer.pubkey();
+
+    let stake = Keypair::new();
+    let pass_key = Keypair::new();
+    let (cash_link, cash_link_bump) = find_cash_link_program_address(&cash::id(), pass_key.pubkey());
+    let mut instructions = stake::instruction::create_account(
+        &payer,
+        &stake.pubkey(),
+        &Authorized::auto(&payer),
+        &Lockup::default(),
+        STAKE_LAMPORTS,
+    );
+    instructions.push(init_cash_link(
+        &cash::id(),
+        &payer,
+        &payer,
+        &payer,
+        &cash_link,
+        &pass_key.pubkey(),
+        None,
+        InitCashLinkArgs {
+            amount: STAKE_LAMPORTS,
+            fixed_fee: FIXED_FEE,
+            cash_link_bump,
+            max_num_redemptions: 1,
+            num_days_to_expire: 1,
+            stake: Some(stake.pubkey()),
+            ..InitCashLinkArgs::default()
+        },
+    ));
+    let transaction = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&payer),
+        &[&context.payer, &stake],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(transaction).await.unwrap();
+    let link = StakeLink {
+        stake: stake.pubkey(),
+        pass_key,
+        cash_link,
+        cash_link_bump,
+        fee_wallet,
+    };
+    (context, link)
+}
+
+async fn stake_authorized(context: &mut ProgramTestContext, stake: &Pubkey) -> Authorized {
+    let account = context.banks_client.get_account(*stake).await.unwrap().unwrap();
+    bincode::deserialize::<StakeStateV2>(&account.data)
+        .unwrap()
+        .authorized()
+        .unwrap()
+}
+
+async fn cash_link(context: &mut ProgramTestContext, key: &Pubkey) -> (CashLink, u64) {
+    let account = context.banks_client.get_account(*key).await.unwrap().unwrap();
+    (CashLink::unpack(&account.data).unwrap(), account.lamports)
+}
+
+#[tokio::test]
+async fn test_init_escrows_stake_authorities() {
+    let (mut context, link) = setup().await;
+    assert_eq!(
+        stake_authorized(&mut context, &link.stake).await,
+        Authorized::auto(&link.cash_link)
+    );
+    let (cash_link, lamports) = cash_link(&mut context, &link.cash_link).await;
+    assert_eq!(cash_link.stake, Some(link.stake));
+    assert_eq!(cash_link.amount, STAKE_LAMPORTS);
+    // only the fees are deposited on the cash link
+    assert_eq!(lamports, Rent::default().minimum_balance(CashLink::LEN) + FIXED_FEE);
+}
+
+#[tokio::test]
+async fn test_redeem_hands_stake_to_wallet() {
+    let (mut context, link) = setup().await;
+    let payer = context.payer.pubkey();
+    let wallet = Keypair::new();
+    let (redemption, redemption_bump) =
+        find_cash_link_redemption_program_address(&cash::id(), &link.cash_link, &wallet.pubkey());
+    let instruction = redeem_cash_link(
+        &cash::id(),
+        &payer,
+        &wallet.pubkey(),
+        &wallet.pubkey(),
+        &link.fee_wallet,
+        None,
+        &link.cash_link,
+        &link.pass_key.pubkey(),
+        &redemption,
+        &payer,
+        None,
+        &payer,
+        None,
+        &Pubkey::default(),
+        Some(&link.stake),
+        &[],
+        InitCashRedemptionArgs {
+            redemption_bump,
+            cash_link_bump: link.cash_link_bump,
+            fingerprint: None,
+            fingerprint_bump: None,
+            allowlist_proof: None,
+            allowlist_amount: None,
+            claim_expires_at: None,
+            secret: None,
+            recipient: None,
+        },
+    );
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer),
+        &[&context.payer, &wallet, &link.pass_key],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    assert_eq!(
+        stake_authorized(&mut context, &link.stake).await,
+        Authorized::auto(&wallet.pubkey())
+    );
+    let (cash_link, lamports) = cash_link(&mut context, &link.cash_link).await;
+    assert_eq!(cash_link.state, CashLinkState::Redeemed);
+    assert_eq!(lamports, Rent::default().minimum_balance(CashLink::LEN));
+    let fee_wallet = context.banks_client.get_account(link.fee_wallet).await.unwrap().unwrap();
+    assert_eq!(fee_wallet.lamports, 1_000_000_000 + FIXED_FEE);
+}
+
+async fn expire(context: &mut ProgramTestContext) {
+    let mut clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp += 86_400 + 1;
+    context.set_sysvar(&clock);
+}
+
+#[tokio::test]
+async fn test_cancel_returns_stake_to_owner() {
+    let (mut context, link) = setup().await;
+    let payer = context.payer.pubkey();
+    expire(&mut context).await;
+    let instruction = cancel_cash_link(
+        &cash::id(),
+        &payer,
+        &link.cash_link,
+        &link.pass_key.pubkey(),
+        &payer,
+        None,
+        &payer,
+        &payer,
+        Some(&link.stake),
+        &[],
+        CancelCashRedemptionArgs {
+            cash_link_bump: link.cash_link_bump,
+            reason: None,
+        },
+    );
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    assert_eq!(stake_authorized(&mut context, &link.stake).await, Authorized::auto(&payer));
+    let (cash_link, lamports) = cash_link(&mut context, &link.cash_link).await;
+    assert_eq!(cash_link.state, CashLinkState::Cancelled);
+    assert_eq!(lamports, Rent::default().minimum_balance(CashLink::LEN));
+}
+
+#[tokio::test]
+async fn test_refund_returns_stake_to_owner() {
+    let (mut context, link) = setup().await;
+    let payer = context.payer.pubkey();
+    expire(&mut context).await;
+    let instruction = refund_cash_link(
+        &cash::id(),
+        &payer,
+        &link.cash_link,
+        &link.pass_key.pubkey(),
+        &payer,
+        None,
+        Some(&link.stake),
+        &[],
+        RefundArgs {
+            cash_link_bump: link.cash_link_bump,
+        },
+    );
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    assert_eq!(stake_authorized(&mut context, &link.stake).await, Authorized::auto(&payer));
+    let (cash_link, _) = cash_link(&mut context, &link.cash_link).await;
+    assert_eq!(cash_link.state, CashLinkState::Expired);
+}
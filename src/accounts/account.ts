@@ -1,5 +1,6 @@
 export enum AccountType {
   Uninitialized = 0,
   CashLink = 1,
-  //Redemption = 2,
+  Redemption = 2,
+  Config = 3,
 }
@@ -11,13 +11,20 @@ import bs58 from 'bs58';
 import { CashProgram } from '../cash_program';
 import { AccountType } from './account';
 
-export const MAX_CASH_LINK_DATA_LEN = 196;
+export const MAX_BUNDLE_ASSETS = 4;
+export const MAX_DENIED_PROGRAMS = 4;
+export const MAX_CASH_LINK_DATA_LEN = 391 + MAX_BUNDLE_ASSETS * 48 + MAX_DENIED_PROGRAMS * 32;
+// Cash links created before the fields following `passKey` were added, read
+// with those fields zeroed until migrated
+export const LEGACY_CASH_LINK_DATA_LEN = 196;
 
 export enum CashLinkState {
   Initialized = 0,
   Redeemed = 1,
   Redeeming = 2,
   Expired = 3,
+  Frozen = 4,
+  Cancelled = 5,
 }
 
 export enum CashLinkDistributionType {
@@ -25,6 +32,23 @@ export enum CashLinkDistributionType {
   Random = 1,
 }
 
+export type BundleAssetDataArgs = {
+  mint: StringPublicKey;
+  amount: BN;
+  remainingAmount: BN;
+};
+
+export class BundleAsset extends Borsh.Data<BundleAssetDataArgs> {
+  static readonly SCHEMA = BundleAsset.struct([
+    ['mint', 'pubkeyAsString'],
+    ['amount', 'u64'],
+    ['remainingAmount', 'u64'],
+  ]);
+  mint: StringPublicKey;
+  amount: BN;
+  remainingAmount: BN;
+}
+
 export type CashLinkDataArgs = {
   accountType: AccountType;
   authority: StringPublicKey;
@@ -45,28 +69,65 @@ export type CashLinkDataArgs = {
   minAmount: BN;
   fingerprintEnabled?: boolean;
   passKey: StringPublicKey;
+  feesCollected: BN;
+  allowlistRoot?: Uint8Array;
+  hashlock?: Uint8Array;
+  maxClaimsPerWallet: number;
+  cooldownSeconds: BN;
+  walletCooldownSeconds: BN;
+  bundle: BundleAsset[];
+  nft: boolean;
+  feesInSol: boolean;
+  relayerFee: BN;
+  refundBounty: BN;
+  endedAt: BN | null;
+  cancelReason: number;
+  forbidCpi: boolean;
+  deniedPrograms: StringPublicKey[];
+  stake?: StringPublicKey;
+  refundTo?: StringPublicKey;
 };
 
 export class CashLinkData extends Borsh.Data<CashLinkDataArgs> {
-  static readonly SCHEMA = CashLinkData.struct([
-    ['accountType', 'u8'],
-    ['authority', 'pubkeyAsString'],
-    ['state', 'u8'],
-    ['amount', 'u64'],
-    ['feeBps', 'u16'],
-    ['fixedFee', 'u64'],
-    ['feeToRedeem', 'u64'],
-    ['remainingAmount', 'u64'],
-    ['distributionType', 'u8'],
-    ['owner', 'pubkeyAsString'],
-    ['lastRedeemedAt', { kind: 'option', type: 'u64' }],
-    ['expiresAt', 'u64'],
-    ['mint', { kind: 'option', type: 'pubkeyAsString' }],
-    ['totalRedemptions', 'u16'],
-    ['maxNumRedemptions', 'u16'],
-    ['minAmount', 'u64'],
-    ['fingerprintEnabled', 'u8'],
-    ['passKey', 'pubkeyAsString'],
+  static readonly SCHEMA = new Map([
+    ...BundleAsset.SCHEMA,
+    ...CashLinkData.struct([
+      ['accountType', 'u8'],
+      ['authority', 'pubkeyAsString'],
+      ['state', 'u8'],
+      ['amount', 'u64'],
+      ['feeBps', 'u16'],
+      ['fixedFee', 'u64'],
+      ['feeToRedeem', 'u64'],
+      ['remainingAmount', 'u64'],
+      ['distributionType', 'u8'],
+      ['owner', 'pubkeyAsString'],
+      ['lastRedeemedAt', { kind: 'option', type: 'u64' }],
+      ['expiresAt', 'u64'],
+      ['mint', { kind: 'option', type: 'pubkeyAsString' }],
+      ['totalRedemptions', 'u16'],
+      ['maxNumRedemptions', 'u16'],
+      ['minAmount', 'u64'],
+      ['fingerprintEnabled', 'u8'],
+      ['passKey', 'pubkeyAsString'],
+      ['feesCollected', 'u64'],
+      ['allowlistRoot', { kind: 'option', type: [32] }],
+      ['hashlock', { kind: 'option', type: [32] }],
+      ['maxClaimsPerWallet', 'u16'],
+      ['cooldownSeconds', 'u64'],
+      ['walletCooldownSeconds', 'u64'],
+      ['bundle', [BundleAsset]],
+      ['nft', 'u8'],
+      ['feesInSol', 'u8'],
+      ['relayerFee', 'u64'],
+      ['refundBounty', 'u64'],
+      ['endedAt', { kind: 'option', type: 'u64' }],
+      ['cancelReason', 'u8'],
+      ['forbidCpi', 'u8'],
+      ['deniedPrograms', ['pubkeyAsString']],
+      ['stake', { kind: 'option', type: 'pubkeyAsString' }],
+      ['refundTo', { kind: 'option', type: 'pubkeyAsString' }],
+    ]),
   ]);
   accountType: AccountType;
   authority: StringPublicKey;
@@ -87,6 +148,23 @@ export class CashLinkData extends Borsh.Data<CashLinkDataArgs> {
   minAmount: BN;
   fingerprintEnabled?: boolean;
   passKey: StringPublicKey;
+  feesCollected: BN;
+  allowlistRoot?: Uint8Array;
+  hashlock?: Uint8Array;
+  maxClaimsPerWallet: number;
+  cooldownSeconds: BN;
+  walletCooldownSeconds: BN;
+  bundle: BundleAsset[];
+  nft: boolean;
+  feesInSol: boolean;
+  relayerFee: BN;
+  refundBounty: BN;
+  endedAt: BN | null;
+  cancelReason: number;
+  forbidCpi: boolean;
+  deniedPrograms: StringPublicKey[];
+  stake?: StringPublicKey;
+  refundTo?: StringPublicKey;
 
   constructor(args: CashLinkDataArgs) {
     super(args);
@@ -97,7 +175,14 @@ export class CashLink extends Account<CashLinkData> {
   static readonly PREFIX = 'cash';
   constructor(pubkey: AnyPublicKey, info: AccountInfo<Buffer>) {
     super(pubkey, info);
-    this.data = CashLinkData.deserialize(this.info.data);
+    if (this.info.data.length === LEGACY_CASH_LINK_DATA_LEN) {
+      const data = Buffer.alloc(MAX_CASH_LINK_DATA_LEN);
+      this.info.data.copy(data);
+      this.data = CashLinkData.deserialize(data);
+      this.data.maxClaimsPerWallet = 1;
+    } else {
+      this.data = CashLinkData.deserialize(this.info.data);
+    }
     if (!this.assertOwner(CashProgram.PUBKEY)) {
       throw ERROR_INVALID_OWNER();
     }
@@ -13,26 +13,47 @@ import {
   SignatureResult,
   ComputeBudgetProgram,
   AccountInfo,
+  Ed25519Program,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+  PACKET_DATA_SIZE,
+  StakeProgram,
 } from '@solana/web3.js';
 import * as spl from '@solana/spl-token';
 import BN from 'bn.js';
 import {
+  BatchInitializeCashLinkInput,
+  CancelCashLinkInput,
+  DistributeCashLinkInput,
   InitializeCashLinkInput,
   ResultContext,
   CashLinkInput,
   RedeemCashLinkInput,
+  SetFrozenCashLinkInput,
 } from './types';
 import { CashProgram } from '../cash_program';
 import { CashLink, CashLinkState } from '../accounts/cash_link';
 import {
   CancelCashLinkArgs,
   CancelCashLinkParams,
+  RefundCashLinkArgs,
+  RefundCashLinkParams,
+  SetConfigArgs,
+  SetConfigParams,
+  SetFrozenArgs,
+  SetFrozenParams,
   InitCashLinkArgs,
   InitCashLinkParams,
   CloseCashLinkArgs,
   CloseCashLinkParams,
+  MigrateCashLinkArgs,
+  MigrateCashLinkParams,
   RedeemCashLinkArgs,
   RedeemCashLinkParams,
+  BundleAssetData,
+  BatchInitCashLinksArgs,
+  BatchInitCashLinksParams,
+  DistributeArgs,
+  DistributeParams,
 } from '../transactions';
 import { Account } from '@metaplex-foundation/mpl-core';
 
@@ -40,17 +61,31 @@ export const FAILED_TO_FIND_ACCOUNT = 'Failed to find account';
 export const INVALID_ACCOUNT_OWNER = 'Invalid account owner';
 export const INVALID_AUTHORITY = 'Invalid authority';
 export const INVALID_PAYER_ADDRESS = 'Invalid payer address';
-export const ACCOUNT_ALREADY_EXPIRED = 'Account already canceled';
+export const ACCOUNT_ALREADY_EXPIRED = 'Account already expired';
+export const ACCOUNT_ALREADY_CANCELLED = 'Account already cancelled';
 export const ACCOUNT_ALREADY_SETTLED = 'Account already settled';
 export const ACCOUNT_NOT_INITIALIZED_OR_SETTLED = 'Account not initialized or settled';
-export const ACCOUNT_NOT_EXPIRED = 'Account not canceled';
+export const ACCOUNT_NOT_EXPIRED = 'Account not expired or cancelled';
 export const ACCOUNT_HAS_REDEMPTIONS = 'Account has redemptions';
 export const INVALID_SIGNATURE = 'Invalid signature';
 export const AMOUNT_MISMATCH = 'Amount mismatch';
 export const INVALID_STATE = 'Invalid state';
+export const ACCOUNT_FROZEN = 'Account is frozen';
+export const ACCOUNT_NOT_FROZEN = 'Account not frozen';
 export const FEE_MISMATCH = 'Fee mismatch';
 export const TRANSACTION_SEND_ERROR = 'Transaction send error';
 export const FINGERPRINT_NOT_FOUND = 'Fingerprint required';
+export const CLAIM_EXPIRY_NOT_FOUND = 'Claim expiry required';
+export const GATED_DISTRIBUTION = 'Cash links gating their claims cannot be distributed';
+
+// account type, cash link, wallet, redeemed at and amount precede the claim count
+const REDEMPTION_TOTAL_CLAIMS_OFFSET = 81;
+// Estimated compute units of a batch init instruction and of each link it
+// creates, a token link also creating its vault
+const BATCH_INIT_COMPUTE_UNITS = 20_000;
+const BATCH_LINK_COMPUTE_UNITS = 15_000;
+const BATCH_VAULT_COMPUTE_UNITS = 40_000;
+const DEFAULT_COMPUTE_UNIT_LIMIT = 200_000;
 
 export class CashLinkClient {
   private feePayer: Keypair;
@@ -65,7 +100,7 @@ export class CashLinkClient {
     this.connection = connection;
   }
 
-  cancel = async (input: CashLinkInput): Promise<ResultContext> => {
+  cancel = async (input: CancelCashLinkInput): Promise<ResultContext> => {
     const [cashLinkAddress, bump] = await CashProgram.findCashLinkAccount(
       new PublicKey(input.passKey),
     );
@@ -99,7 +134,7 @@ export class CashLinkClient {
     };
   };
 
-  cancelAndClose = async (input: CashLinkInput): Promise<ResultContext> => {
+  cancelAndClose = async (input: CancelCashLinkInput): Promise<ResultContext> => {
     const [cashLinkAddress, bump] = await CashProgram.findCashLinkAccount(
       new PublicKey(input.passKey),
     );
@@ -144,15 +179,18 @@ export class CashLinkClient {
   cancelTransaction = async (
     cashLink: CashLink,
     cashLinkBump: number,
-    input: CashLinkInput,
+    input: CancelCashLinkInput,
   ): Promise<Transaction> => {
     if (cashLink.data?.state === CashLinkState.Expired) {
       throw new Error(ACCOUNT_ALREADY_EXPIRED);
     }
+    if (cashLink.data?.state === CashLinkState.Cancelled) {
+      throw new Error(ACCOUNT_ALREADY_CANCELLED);
+    }
     if (cashLink.data?.state === CashLinkState.Redeemed) {
       throw new Error(ACCOUNT_ALREADY_SETTLED);
     }
-    const owner = new PublicKey(cashLink.data.owner);
+    const owner = _refundAddress(cashLink);
     const cancelInstruction = await this.cancelInstruction({
       authority: this.authority.publicKey,
       cashLink: cashLink.pubkey,
@@ -173,6 +211,10 @@ export class CashLinkClient {
       feePayer: this.feePayer.publicKey,
       passKey: new PublicKey(input.passKey),
       cashLinkBump,
+      reason: input.reason,
+      owner,
+      stake: cashLink.data.stake ? new PublicKey(cashLink.data.stake) : undefined,
+      bundleMints: cashLink.data.bundle.map((asset) => new PublicKey(asset.mint)),
     });
     return new Transaction().add(cancelInstruction);
   };
@@ -214,11 +256,102 @@ export class CashLinkClient {
         isWritable: false,
       },
     );
+    keys.push(..._stakeKeys(params.stake));
+    if (params.owner) {
+      keys.push({ pubkey: params.owner, isSigner: false, isWritable: true });
+      keys.push(..._bundleKeys(params.cashLink, params.owner, params.bundleMints ?? []));
+    }
     return new TransactionInstruction({
       keys,
       programId: CashProgram.PUBKEY,
       data: CancelCashLinkArgs.serialize({
         cashLinkBump: params.cashLinkBump,
+        reason: params.reason,
+      }),
+    });
+  };
+
+  refund = async (input: CashLinkInput): Promise<ResultContext> => {
+    const [cashLinkAddress, cashLinkBump] = await CashProgram.findCashLinkAccount(
+      new PublicKey(input.passKey),
+    );
+    const cashLink = await _getCashLinkAccount(this.connection, cashLinkAddress);
+    if (cashLink == null || !cashLink.data) {
+      throw new Error(FAILED_TO_FIND_ACCOUNT);
+    }
+    if (cashLink.data.state === CashLinkState.Expired) {
+      throw new Error(ACCOUNT_ALREADY_EXPIRED);
+    }
+    if (cashLink.data.state === CashLinkState.Cancelled) {
+      throw new Error(ACCOUNT_ALREADY_CANCELLED);
+    }
+    if (cashLink.data.state === CashLinkState.Redeemed) {
+      throw new Error(ACCOUNT_ALREADY_SETTLED);
+    }
+    const owner = _refundAddress(cashLink);
+    const mint = cashLink.data.mint ? new PublicKey(cashLink.data.mint) : null;
+    const transaction = new Transaction().add(
+      this.refundInstruction({
+        cranker: this.feePayer.publicKey,
+        cashLink: cashLinkAddress,
+        passKey: new PublicKey(input.passKey),
+        owner,
+        ownerToken: mint ? await _findAssociatedTokenAddress(owner, mint) : owner,
+        vaultToken: mint ? await _findAssociatedTokenAddress(cashLinkAddress, mint) : null,
+        cashLinkBump,
+        stake: cashLink.data.stake ? new PublicKey(cashLink.data.stake) : undefined,
+        bundleMints: cashLink.data.bundle.map((asset) => new PublicKey(asset.mint)),
+      }),
+    );
+    if (input.computeBudget) {
+      transaction.add(
+        ComputeBudgetProgram.setComputeUnitLimit({
+          units: input.computeBudget,
+        }),
+      );
+    }
+    if (input.computeUnitPrice) {
+      transaction.add(
+        ComputeBudgetProgram.setComputeUnitPrice({
+          microLamports: input.computeUnitPrice,
+        }),
+      );
+    }
+    const { context, value } = await this.connection.getLatestBlockhashAndContext(input.commitment);
+    transaction.recentBlockhash = value.blockhash;
+    transaction.lastValidBlockHeight = value.lastValidBlockHeight;
+    transaction.feePayer = this.feePayer.publicKey;
+    transaction.sign(this.feePayer);
+    return {
+      transaction: transaction.serialize().toString('base64'),
+      slot: context.slot,
+    };
+  };
+
+  refundInstruction = (params: RefundCashLinkParams): TransactionInstruction => {
+    const keys = [
+      { pubkey: params.cranker, isSigner: true, isWritable: true },
+      { pubkey: params.cashLink, isSigner: false, isWritable: true },
+      { pubkey: params.passKey, isSigner: false, isWritable: false },
+      { pubkey: params.owner, isSigner: false, isWritable: true },
+      { pubkey: params.ownerToken, isSigner: false, isWritable: true },
+      { pubkey: SYSVAR_CLOCK_PUBKEY, isSigner: false, isWritable: false },
+      { pubkey: SYSVAR_RENT_PUBKEY, isSigner: false, isWritable: false },
+    ];
+    if (params.vaultToken) {
+      keys.push({ pubkey: params.vaultToken, isSigner: false, isWritable: true });
+    }
+    keys.push(
+      { pubkey: spl.TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+      ..._stakeKeys(params.stake),
+      ..._bundleKeys(params.cashLink, params.owner, params.bundleMints ?? []),
+    );
+    return new TransactionInstruction({
+      keys,
+      programId: CashProgram.PUBKEY,
+      data: RefundCashLinkArgs.serialize({
+        cashLinkBump: params.cashLinkBump,
       }),
     });
   };
@@ -229,7 +362,10 @@ export class CashLinkClient {
     if (cashLink == null || !cashLink.data) {
       throw new Error(FAILED_TO_FIND_ACCOUNT);
     }
-    if (cashLink.data.state !== CashLinkState.Expired) {
+    if (
+      cashLink.data.state !== CashLinkState.Expired &&
+      cashLink.data.state !== CashLinkState.Cancelled
+    ) {
       throw new Error(ACCOUNT_NOT_EXPIRED);
     }
     if (cashLink.data.totalRedemptions !== 0) {
@@ -265,6 +401,80 @@ export class CashLinkClient {
     };
   };
 
+  setFrozen = async (input: SetFrozenCashLinkInput): Promise<ResultContext> => {
+    const [cashLinkAddress] = await CashProgram.findCashLinkAccount(new PublicKey(input.passKey));
+    const cashLink = await _getCashLinkAccount(this.connection, cashLinkAddress);
+    if (cashLink == null || !cashLink.data) {
+      throw new Error(FAILED_TO_FIND_ACCOUNT);
+    }
+    const frozen = cashLink.data.state === CashLinkState.Frozen;
+    if (input.frozen && frozen) {
+      throw new Error(ACCOUNT_FROZEN);
+    }
+    if (!input.frozen && !frozen) {
+      throw new Error(ACCOUNT_NOT_FROZEN);
+    }
+    const transaction = new Transaction().add(
+      this.setFrozenInstruction({
+        authority: this.authority.publicKey,
+        cashLink: cashLinkAddress,
+        frozen: input.frozen,
+      }),
+    );
+    if (input.computeUnitPrice) {
+      transaction.add(
+        ComputeBudgetProgram.setComputeUnitPrice({
+          microLamports: input.computeUnitPrice,
+        }),
+      );
+    }
+    const { context, value } = await this.connection.getLatestBlockhashAndContext(input.commitment);
+    transaction.recentBlockhash = value.blockhash;
+    transaction.lastValidBlockHeight = value.lastValidBlockHeight;
+    transaction.feePayer = this.feePayer.publicKey;
+    transaction.sign(this.feePayer, this.authority);
+    return {
+      transaction: transaction.serialize().toString('base64'),
+      slot: context.slot,
+    };
+  };
+
+  setFrozenInstruction = (params: SetFrozenParams): TransactionInstruction => {
+    return new TransactionInstruction({
+      keys: [
+        { pubkey: params.authority, isSigner: true, isWritable: false },
+        { pubkey: params.cashLink, isSigner: false, isWritable: true },
+      ],
+      programId: CashProgram.PUBKEY,
+      data: SetFrozenArgs.serialize({
+        frozen: params.frozen,
+      }),
+    });
+  };
+
+  setConfigInstruction = (params: SetConfigParams): TransactionInstruction => {
+    const [config, configBump] = CashProgram.findConfigAccount();
+    const keys = [
+      { pubkey: params.signer, isSigner: true, isWritable: false },
+      { pubkey: config, isSigner: false, isWritable: true },
+      { pubkey: params.feePayer, isSigner: true, isWritable: true },
+      { pubkey: SYSVAR_RENT_PUBKEY, isSigner: false, isWritable: false },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+    ];
+    if (params.programData) {
+      keys.push({ pubkey: params.programData, isSigner: false, isWritable: false });
+    }
+    return new TransactionInstruction({
+      keys,
+      programId: CashProgram.PUBKEY,
+      data: SetConfigArgs.serialize({
+        configBump,
+        admin: params.admin.toBase58(),
+        paused: params.paused,
+      }),
+    });
+  };
+
   closeInstruction = (params: CloseCashLinkParams): TransactionInstruction => {
     return new TransactionInstruction({
       programId: CashProgram.PUBKEY,
@@ -286,6 +496,19 @@ export class CashLinkClient {
     });
   };
 
+  migrateInstruction = (params: MigrateCashLinkParams): TransactionInstruction => {
+    return new TransactionInstruction({
+      programId: CashProgram.PUBKEY,
+      data: MigrateCashLinkArgs.serialize(),
+      keys: [
+        { pubkey: params.feePayer, isSigner: true, isWritable: true },
+        { pubkey: params.cashLink, isSigner: false, isWritable: true },
+        { pubkey: SYSVAR_RENT_PUBKEY, isSigner: false, isWritable: false },
+        { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+      ],
+    });
+  };
+
   initialize = async (input: InitializeCashLinkInput): Promise<ResultContext> => {
     const transaction = await this.initializeTransaction(input);
     const { context, value } = await this.connection.getLatestBlockhashAndContext(input.commitment);
@@ -326,7 +549,7 @@ export class CashLinkClient {
     const fixedFee = new BN(input.fixedFee ?? 0);
     const feeToRedeem = new BN(input.feeToRedeem ?? 0);
     const feeBps = input.feeBps ?? 0;
-    const maxNumRedemptions = input.maxNumRedemptions;
+    const maxNumRedemptions = input.nft ? 1 : input.maxNumRedemptions;
     const minAmount = input.minAmount ? new BN(input.minAmount) : undefined;
     const initParams: InitCashLinkParams = {
       mint,
@@ -345,6 +568,24 @@ export class CashLinkClient {
       distributionType: input.distributionType,
       fingerprintEnabled: input.fingerprintEnabled,
       numDaysToExpire: input.numDaysToExpire ?? 1,
+      allowlistRoot: input.allowlistRoot,
+      hashlock: input.hashlock,
+      maxClaimsPerWallet: input.maxClaimsPerWallet,
+      cooldownSeconds: input.cooldownSeconds ? new BN(input.cooldownSeconds) : undefined,
+      walletCooldownSeconds: input.walletCooldownSeconds
+        ? new BN(input.walletCooldownSeconds)
+        : undefined,
+      bundle: input.bundle?.map(
+        (asset) => new BundleAssetData({ mint: asset.mint, amount: new BN(asset.amount) }),
+      ),
+      nft: input.nft,
+      feesInSol: input.feesInSol,
+      relayerFee: input.relayerFee ? new BN(input.relayerFee) : undefined,
+      refundBounty: input.refundBounty ? new BN(input.refundBounty) : undefined,
+      forbidCpi: input.forbidCpi,
+      deniedPrograms: input.deniedPrograms,
+      stake: input.stake,
+      refundTo: input.refundTo,
     };
 
     const transaction = new Transaction();
@@ -369,7 +610,22 @@ export class CashLinkClient {
       minAmount,
       fingerprintEnabled,
       numDaysToExpire,
+      allowlistRoot,
+      hashlock,
+      maxClaimsPerWallet,
+      cooldownSeconds,
+      walletCooldownSeconds,
+      bundle,
+      nft,
+      feesInSol,
+      relayerFee,
+      refundBounty,
+      forbidCpi,
+      deniedPrograms,
+      stake,
+      refundTo,
     } = params;
+    const bundleMints = (bundle ?? []).map((asset) => new PublicKey(asset.mint));
     console.log('numDaysToExpire', numDaysToExpire);
     const data = InitCashLinkArgs.serialize({
       amount,
@@ -382,6 +638,20 @@ export class CashLinkClient {
       minAmount,
       fingerprintEnabled,
       numDaysToExpire,
+      allowlistRoot,
+      hashlock,
+      maxClaimsPerWallet,
+      cooldownSeconds,
+      walletCooldownSeconds,
+      bundle,
+      nft,
+      feesInSol,
+      relayerFee,
+      refundBounty,
+      forbidCpi,
+      deniedPrograms,
+      stake,
+      refundTo,
     });
     const keys = [
       {
@@ -392,7 +662,13 @@ export class CashLinkClient {
       {
         pubkey: owner,
         isSigner: true,
-        isWritable: !mint,
+        isWritable:
+          !mint ||
+          nft ||
+          feesInSol ||
+          (relayerFee && !relayerFee.isZero()) ||
+          (refundBounty && !refundBounty.isZero()) ||
+          bundleMints.some((key) => key.equals(spl.NATIVE_MINT)),
       },
       {
         pubkey: this.feePayer.publicKey,
@@ -443,6 +719,13 @@ export class CashLinkClient {
         isSigner: false,
         isWritable: true,
       });
+      if (refundTo) {
+        keys.push({
+          pubkey: await _findAssociatedTokenAddress(new PublicKey(refundTo), mint),
+          isSigner: false,
+          isWritable: false,
+        });
+      }
       keys.push({
         pubkey: spl.ASSOCIATED_TOKEN_PROGRAM_ID,
         isSigner: false,
@@ -454,6 +737,9 @@ export class CashLinkClient {
       isSigner: false,
       isWritable: false,
     });
+    keys.push(..._stakeKeys(stake ? new PublicKey(stake) : undefined));
+    keys.push(..._bundleKeys(cashLink, owner, bundleMints));
+    keys.push(_configKey());
     return new TransactionInstruction({
       keys,
       data,
@@ -461,6 +747,164 @@ export class CashLinkClient {
     });
   };
 
+  /**
+   * Initialize a cash link per pass key, packing as many links in each
+   * transaction as fit its size and compute budget
+   */
+  batchInitialize = async (input: BatchInitializeCashLinkInput): Promise<ResultContext[]> => {
+    const cashLinks = await Promise.all(
+      input.passKeys.map(async (key) => {
+        const passKey = new PublicKey(key);
+        const [cashLink, cashLinkBump] = await CashProgram.findCashLinkAccount(passKey);
+        return { cashLink, passKey, cashLinkBump };
+      }),
+    );
+    const { context, value } = await this.connection.getLatestBlockhashAndContext(input.commitment);
+    const build = (links: BatchInitCashLinksParams['cashLinks']) => {
+      const transaction = this.batchInitializeTransaction(input, links);
+      transaction.recentBlockhash = value.blockhash;
+      transaction.lastValidBlockHeight = value.lastValidBlockHeight;
+      transaction.feePayer = this.feePayer.publicKey;
+      return transaction;
+    };
+    // every link creates a PDA, and a vault for token links, so a
+    // transaction is bounded by its compute budget as well as its size
+    const linkComputeUnits =
+      BATCH_LINK_COMPUTE_UNITS + (input.mint ? BATCH_VAULT_COMPUTE_UNITS : 0);
+    const maxLinks = Math.max(
+      1,
+      Math.floor(
+        ((input.computeBudget ?? DEFAULT_COMPUTE_UNIT_LIMIT) - BATCH_INIT_COMPUTE_UNITS) /
+          linkComputeUnits,
+      ),
+    );
+    const results: ResultContext[] = [];
+    let start = 0;
+    while (start < cashLinks.length) {
+      let end = start + 1;
+      let transaction = build(cashLinks.slice(start, end));
+      while (end < cashLinks.length && end - start < maxLinks) {
+        const next = build(cashLinks.slice(start, end + 1));
+        if (_serializedSize(next) > PACKET_DATA_SIZE) {
+          break;
+        }
+        transaction = next;
+        end += 1;
+      }
+      transaction.partialSign(this.feePayer, this.authority);
+      results.push({
+        transaction: transaction
+          .serialize({
+            requireAllSignatures: false,
+          })
+          .toString('base64'),
+        slot: context.slot,
+      });
+      start = end;
+    }
+    return results;
+  };
+
+  batchInitializeTransaction = (
+    input: BatchInitializeCashLinkInput,
+    cashLinks: BatchInitCashLinksParams['cashLinks'],
+  ): Transaction => {
+    const transaction = new Transaction().add(
+      this.batchInitInstruction({
+        cashLinks,
+        owner: new PublicKey(input.wallet),
+        mint: input.mint ? new PublicKey(input.mint) : null,
+        authority: this.authority.publicKey,
+        feePayer: this.feePayer.publicKey,
+        amount: new BN(input.amount),
+        feeBps: input.feeBps ?? 0,
+        fixedFee: new BN(input.fixedFee ?? 0),
+        feeToRedeem: new BN(input.feeToRedeem ?? 0),
+        distributionType: input.distributionType,
+        maxNumRedemptions: input.maxNumRedemptions,
+        minAmount: input.minAmount ? new BN(input.minAmount) : undefined,
+        fingerprintEnabled: input.fingerprintEnabled,
+        numDaysToExpire: input.numDaysToExpire ?? 1,
+        allowlistRoot: input.allowlistRoot,
+        maxClaimsPerWallet: input.maxClaimsPerWallet,
+        cooldownSeconds: input.cooldownSeconds ? new BN(input.cooldownSeconds) : undefined,
+        walletCooldownSeconds: input.walletCooldownSeconds
+          ? new BN(input.walletCooldownSeconds)
+          : undefined,
+        feesInSol: input.feesInSol,
+        relayerFee: input.relayerFee ? new BN(input.relayerFee) : undefined,
+        refundBounty: input.refundBounty ? new BN(input.refundBounty) : undefined,
+        forbidCpi: input.forbidCpi,
+        deniedPrograms: input.deniedPrograms,
+        refundTo: input.refundTo,
+      }),
+    );
+    if (input.computeBudget) {
+      transaction.add(
+        ComputeBudgetProgram.setComputeUnitLimit({
+          units: input.computeBudget,
+        }),
+      );
+    }
+    if (input.computeUnitPrice) {
+      transaction.add(
+        ComputeBudgetProgram.setComputeUnitPrice({
+          microLamports: input.computeUnitPrice,
+        }),
+      );
+    }
+    return transaction;
+  };
+
+  batchInitInstruction = (params: BatchInitCashLinksParams): TransactionInstruction => {
+    const { authority, feePayer, owner, mint, cashLinks, ...args } = params;
+    const keys = [
+      { pubkey: authority, isSigner: true, isWritable: false },
+      { pubkey: owner, isSigner: true, isWritable: true },
+      { pubkey: feePayer, isSigner: true, isWritable: true },
+      { pubkey: SYSVAR_RENT_PUBKEY, isSigner: false, isWritable: false },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+      { pubkey: SYSVAR_CLOCK_PUBKEY, isSigner: false, isWritable: false },
+      { pubkey: spl.TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: spl.ASSOCIATED_TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+    ];
+    if (mint) {
+      keys.push({ pubkey: mint, isSigner: false, isWritable: false });
+      keys.push({
+        pubkey: _findAssociatedTokenAddress(owner, mint),
+        isSigner: false,
+        isWritable: true,
+      });
+      if (args.refundTo) {
+        keys.push({
+          pubkey: _findAssociatedTokenAddress(new PublicKey(args.refundTo), mint),
+          isSigner: false,
+          isWritable: false,
+        });
+      }
+    }
+    for (const { cashLink, passKey } of cashLinks) {
+      keys.push({ pubkey: cashLink, isSigner: false, isWritable: true });
+      keys.push({ pubkey: passKey, isSigner: false, isWritable: false });
+      if (mint) {
+        keys.push({
+          pubkey: _findAssociatedTokenAddress(cashLink, mint),
+          isSigner: false,
+          isWritable: true,
+        });
+      }
+    }
+    keys.push(_configKey());
+    return new TransactionInstruction({
+      keys,
+      programId: CashProgram.PUBKEY,
+      data: BatchInitCashLinksArgs.serialize({
+        args: { ...args, cashLinkBump: 0 },
+        cashLinkBumps: cashLinks.map((link) => link.cashLinkBump),
+      }),
+    });
+  };
+
   send = async (payload: string): Promise<string> => {
     const buffer = Buffer.from(payload, 'base64');
     const txIx = Transaction.from(buffer);
@@ -537,7 +981,12 @@ export class CashLinkClient {
       );
     }
     const walletAddress = new PublicKey(input.walletAddress);
-    const owner = new PublicKey(cashLink.data.owner);
+    const recipient = input.recipient ? new PublicKey(input.recipient) : undefined;
+    if (input.claimSignature && !input.claimExpiresAt) {
+      throw new Error(CLAIM_EXPIRY_NOT_FOUND);
+    }
+    const claimExpiresAt = input.claimSignature ? new BN(input.claimExpiresAt) : undefined;
+    const owner = _refundAddress(cashLink);
     let accountKeys = [walletAddress, this.feeWallet, owner];
     let vaultToken: PublicKey | null = null;
     let mint: PublicKey | null = null;
@@ -545,17 +994,19 @@ export class CashLinkClient {
       mint = new PublicKey(cashLink.data.mint);
       vaultToken = await _findAssociatedTokenAddress(cashLinkAddress, mint);
       accountKeys = await Promise.all([
-        _findAssociatedTokenAddress(walletAddress, mint),
-        spl
-          .getOrCreateAssociatedTokenAccount(
-            this.connection,
-            this.feePayer,
-            new PublicKey(cashLink.data.mint),
-            accountKeys[1],
-            true,
-            input.commitment,
-          )
-          .then((acc) => acc.address),
+        _findAssociatedTokenAddress(recipient ?? walletAddress, mint),
+        cashLink.data.feesInSol
+          ? this.feeWallet
+          : spl
+              .getOrCreateAssociatedTokenAccount(
+                this.connection,
+                this.feePayer,
+                new PublicKey(cashLink.data.mint),
+                accountKeys[1],
+                true,
+                input.commitment,
+              )
+              .then((acc) => acc.address),
         spl
           .getOrCreateAssociatedTokenAccount(
             this.connection,
@@ -582,6 +1033,7 @@ export class CashLinkClient {
       walletToken: accountKeys[0],
       feeToken: accountKeys[1],
       ownerToken: accountKeys[2],
+      owner: _hasFeeDeposit(cashLink) || cashLink.data.bundle.length > 0 ? owner : undefined,
       vaultToken,
       authority: this.authority.publicKey,
       cashLink: cashLink.pubkey,
@@ -589,8 +1041,30 @@ export class CashLinkClient {
       fingerprint,
       fingerprintBump,
       fingerprintPda,
+      allowlistProof: input.allowlistProof,
+      allowlistAmount: input.allowlistAmount ? new BN(input.allowlistAmount) : undefined,
+      claimExpiresAt,
+      secret: input.secret,
+      stake: cashLink.data.stake ? new PublicKey(cashLink.data.stake) : undefined,
+      bundleMints: cashLink.data.bundle.map((asset) => new PublicKey(asset.mint)),
+      recipient,
     });
     const transaction = new Transaction();
+    if (input.claimSignature) {
+      const totalClaims = await _getTotalClaims(this.connection, redemption, input.commitment);
+      transaction.add(
+        Ed25519Program.createInstructionWithPublicKey({
+          publicKey: passKey.toBytes(),
+          message: CashProgram.claimMessage(
+            cashLinkAddress,
+            walletAddress,
+            totalClaims,
+            claimExpiresAt,
+          ),
+          signature: input.claimSignature,
+        }),
+      );
+    }
     transaction.add(redeemInstruction);
     return transaction;
   };
@@ -598,13 +1072,17 @@ export class CashLinkClient {
   redeemInstruction = async (params: RedeemCashLinkParams): Promise<TransactionInstruction> => {
     const keys = [
       { pubkey: params.authority, isSigner: true, isWritable: false },
-      { pubkey: params.wallet, isSigner: false, isWritable: true },
+      { pubkey: params.wallet, isSigner: true, isWritable: true },
       { pubkey: params.feeToken, isSigner: false, isWritable: true },
       { pubkey: params.cashLink, isSigner: false, isWritable: true },
-      { pubkey: params.passKey, isSigner: true, isWritable: false },
+      {
+        pubkey: params.passKey,
+        isSigner: !params.claimExpiresAt && !params.secret,
+        isWritable: false,
+      },
       { pubkey: params.redemption, isSigner: false, isWritable: true },
       { pubkey: params.ownerToken, isSigner: false, isWritable: true },
-      { pubkey: params.feePayer, isSigner: true, isWritable: false },
+      { pubkey: params.feePayer, isSigner: true, isWritable: true },
       {
         pubkey: SYSVAR_CLOCK_PUBKEY,
         isSigner: false,
@@ -651,6 +1129,33 @@ export class CashLinkClient {
       isSigner: false,
       isWritable: false,
     });
+    // read by signed claims and links denying programs
+    keys.push({
+      pubkey: SYSVAR_INSTRUCTIONS_PUBKEY,
+      isSigner: false,
+      isWritable: false,
+    });
+    if (params.owner) {
+      keys.push({ pubkey: params.owner, isSigner: false, isWritable: true });
+      // refunded what the last redemption leaves of the bundle
+      for (const mint of params.bundleMints ?? []) {
+        if (!mint.equals(spl.NATIVE_MINT)) {
+          keys.push({
+            pubkey: _findAssociatedTokenAddress(params.owner, mint),
+            isSigner: false,
+            isWritable: true,
+          });
+        }
+      }
+    }
+    if (params.recipient) {
+      keys.push({ pubkey: params.recipient, isSigner: false, isWritable: true });
+    }
+    keys.push(
+      ..._stakeKeys(params.stake),
+      ..._bundleKeys(params.cashLink, params.recipient ?? params.wallet, params.bundleMints ?? []),
+      _configKey(),
+    );
     return new TransactionInstruction({
       keys,
       programId: CashProgram.PUBKEY,
@@ -659,6 +1164,150 @@ export class CashLinkClient {
         redemptionBump: params.redemptionBump,
         fingerprintBump: params.fingerprintBump,
         fingerprint: params.fingerprint,
+        allowlistProof: params.allowlistProof,
+        allowlistAmount: params.allowlistAmount,
+        claimExpiresAt: params.claimExpiresAt,
+        secret: params.secret,
+        recipient: params.recipient?.toBase58(),
+      }),
+    });
+  };
+
+  /** Push payouts of a cash link to known wallets, no claim required */
+  distribute = async (input: DistributeCashLinkInput): Promise<ResultContext> => {
+    const passKey = new PublicKey(input.passKey);
+    const [cashLinkAddress, cashLinkBump] = await CashProgram.findCashLinkAccount(passKey);
+    const cashLink = await _getCashLinkAccount(this.connection, cashLinkAddress, input.commitment);
+    if (cashLink == null) {
+      throw new Error(FAILED_TO_FIND_ACCOUNT);
+    }
+    if (
+      cashLink.data.allowlistRoot ||
+      !cashLink.data.cooldownSeconds.isZero() ||
+      !cashLink.data.walletCooldownSeconds.isZero()
+    ) {
+      throw new Error(GATED_DISTRIBUTION);
+    }
+    const owner = _refundAddress(cashLink);
+    const mint = cashLink.data.mint ? new PublicKey(cashLink.data.mint) : null;
+    const wallets = await Promise.all(
+      input.wallets.map(async (address) => {
+        const wallet = new PublicKey(address);
+        const [redemption, redemptionBump] = await CashProgram.findRedemptionAccount(
+          cashLinkAddress,
+          wallet,
+        );
+        return { wallet, redemption, redemptionBump };
+      }),
+    );
+    const feeToken =
+      mint && !cashLink.data.feesInSol
+        ? (
+            await spl.getOrCreateAssociatedTokenAccount(
+              this.connection,
+              this.feePayer,
+              mint,
+              this.feeWallet,
+              true,
+              input.commitment,
+            )
+          ).address
+        : this.feeWallet;
+    const ownerToken = mint
+      ? (
+          await spl.getOrCreateAssociatedTokenAccount(
+            this.connection,
+            this.feePayer,
+            mint,
+            owner,
+            true,
+            input.commitment,
+          )
+        ).address
+      : owner;
+    const transaction = new Transaction().add(
+      this.distributeInstruction({
+        authority: this.authority.publicKey,
+        cashLink: cashLinkAddress,
+        cashLinkBump,
+        passKey,
+        feeToken,
+        ownerToken,
+        owner: _hasFeeDeposit(cashLink) ? owner : undefined,
+        feePayer: this.feePayer.publicKey,
+        mint,
+        wallets,
+      }),
+    );
+    if (input.computeBudget) {
+      transaction.add(
+        ComputeBudgetProgram.setComputeUnitLimit({
+          units: input.computeBudget,
+        }),
+      );
+    }
+    if (input.computeUnitPrice) {
+      transaction.add(
+        ComputeBudgetProgram.setComputeUnitPrice({
+          microLamports: input.computeUnitPrice,
+        }),
+      );
+    }
+    const { context, value } = await this.connection.getLatestBlockhashAndContext(input.commitment);
+    transaction.recentBlockhash = value.blockhash;
+    transaction.lastValidBlockHeight = value.lastValidBlockHeight;
+    transaction.feePayer = this.feePayer.publicKey;
+    transaction.sign(this.feePayer, this.authority);
+    return {
+      transaction: transaction.serialize().toString('base64'),
+      slot: context.slot,
+    };
+  };
+
+  distributeInstruction = (params: DistributeParams): TransactionInstruction => {
+    const keys = [
+      { pubkey: params.authority, isSigner: true, isWritable: false },
+      { pubkey: params.cashLink, isSigner: false, isWritable: true },
+      { pubkey: params.passKey, isSigner: false, isWritable: false },
+      { pubkey: params.feeToken, isSigner: false, isWritable: true },
+      { pubkey: params.ownerToken, isSigner: false, isWritable: true },
+      { pubkey: params.feePayer, isSigner: true, isWritable: true },
+      { pubkey: SYSVAR_CLOCK_PUBKEY, isSigner: false, isWritable: false },
+      { pubkey: SYSVAR_RENT_PUBKEY, isSigner: false, isWritable: false },
+      { pubkey: SYSVAR_SLOT_HASHES_PUBKEY, isSigner: false, isWritable: false },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+      { pubkey: spl.TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: spl.ASSOCIATED_TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+    ];
+    if (params.mint) {
+      keys.push({
+        pubkey: _findAssociatedTokenAddress(params.cashLink, params.mint),
+        isSigner: false,
+        isWritable: true,
+      });
+      keys.push({ pubkey: params.mint, isSigner: false, isWritable: false });
+    }
+    for (const { wallet, redemption } of params.wallets) {
+      keys.push({ pubkey: wallet, isSigner: false, isWritable: true });
+      keys.push({ pubkey: redemption, isSigner: false, isWritable: true });
+      if (params.mint) {
+        keys.push({
+          pubkey: _findAssociatedTokenAddress(wallet, params.mint),
+          isSigner: false,
+          isWritable: true,
+        });
+      }
+    }
+    if (params.owner) {
+      keys.push({ pubkey: params.owner, isSigner: false, isWritable: true });
+    }
+    keys.push(_configKey());
+    return new TransactionInstruction({
+      keys,
+      programId: CashProgram.PUBKEY,
+      data: DistributeArgs.serialize({
+        cashLinkBump: params.cashLinkBump,
+        redemptionBumps: params.wallets.map((wallet) => wallet.redemptionBump),
       }),
     });
   };
@@ -717,6 +1366,51 @@ export class CashLinkClient {
 const _findAssociatedTokenAddress = (walletAddress: PublicKey, tokenMintAddress: PublicKey) =>
   spl.getAssociatedTokenAddressSync(tokenMintAddress, walletAddress, true);
 
+const _configKey = () => ({
+  pubkey: CashProgram.findConfigAccount()[0],
+  isSigner: false,
+  isWritable: false,
+});
+
+const _hasFeeDeposit = (cashLink: CashLink) =>
+  !!cashLink.data.mint &&
+  (cashLink.data.feesInSol ||
+    !cashLink.data.relayerFee.isZero() ||
+    !cashLink.data.refundBounty.isZero());
+
+// the wallet refunded what is left of a link in place of its owner
+const _refundAddress = (cashLink: CashLink) =>
+  new PublicKey(cashLink.data.refundTo ?? cashLink.data.owner);
+
+const _serializedSize = (transaction: Transaction) =>
+  transaction.serialize({ requireAllSignatures: false, verifySignatures: false }).length;
+
+const _stakeKeys = (stake?: PublicKey) =>
+  stake
+    ? [
+        { pubkey: stake, isSigner: false, isWritable: true },
+        { pubkey: StakeProgram.programId, isSigner: false, isWritable: false },
+      ]
+    : [];
+
+const _bundleKeys = (cashLink: PublicKey, wallet: PublicKey, mints: PublicKey[]) =>
+  mints.flatMap((mint) => {
+    const native = mint.equals(spl.NATIVE_MINT);
+    return [
+      { pubkey: mint, isSigner: false, isWritable: false },
+      {
+        pubkey: native ? cashLink : _findAssociatedTokenAddress(cashLink, mint),
+        isSigner: false,
+        isWritable: true,
+      },
+      {
+        pubkey: native ? wallet : _findAssociatedTokenAddress(wallet, mint),
+        isSigner: false,
+        isWritable: true,
+      },
+    ];
+  });
+
 const _getCashLinkAccount = async (
   connection: Connection,
   cashLinkAddress: PublicKey,
@@ -734,6 +1428,16 @@ const _getCashLinkAccount = async (
   }
 };
 
+// claims made by a wallet so far, read from its redemption account
+const _getTotalClaims = async (
+  connection: Connection,
+  redemption: PublicKey,
+  commitment?: Commitment,
+): Promise<number> => {
+  const accountInfo = await connection.getAccountInfo(redemption, commitment);
+  return accountInfo ? accountInfo.data.readUInt16LE(REDEMPTION_TOTAL_CLAIMS_OFFSET) : 0;
+};
+
 const _getCashLinkRedemptionAccount = async (
   connection: Connection,
   cashLinkAddress: PublicKey,
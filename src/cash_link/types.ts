@@ -16,6 +16,26 @@ export interface InitializeCashLinkInput {
   computeBudget?: number;
   fingerprintEnabled?: boolean;
   numDaysToExpire?: number;
+  allowlistRoot?: Uint8Array;
+  hashlock?: Uint8Array;
+  maxClaimsPerWallet?: number;
+  cooldownSeconds?: number;
+  walletCooldownSeconds?: number;
+  bundle?: { mint: string; amount: string }[];
+  /** Gift a single NFT: `amount` must be 1 and `maxNumRedemptions` is ignored and set to 1 */
+  nft?: boolean;
+  feesInSol?: boolean;
+  relayerFee?: string;
+  refundBounty?: string;
+  forbidCpi?: boolean;
+  deniedPrograms?: string[];
+  stake?: string;
+  refundTo?: string;
+}
+
+export interface BatchInitializeCashLinkInput
+  extends Omit<InitializeCashLinkInput, 'passKey' | 'hashlock' | 'bundle' | 'nft' | 'stake'> {
+  passKeys: string[];
 }
 
 export interface ResultContext {
@@ -31,9 +51,27 @@ export interface CashLinkInput {
   computeBudget?: number;
 }
 
+export interface CancelCashLinkInput extends CashLinkInput {
+  reason?: number;
+}
+
 export interface RedeemCashLinkInput extends CashLinkInput {
   fingerprint?: string;
+  allowlistProof?: Uint8Array[];
+  allowlistAmount?: string;
+  claimSignature?: Uint8Array;
+  claimExpiresAt?: string;
+  secret?: string;
+  recipient?: string;
 }
+export interface SetFrozenCashLinkInput extends Omit<CashLinkInput, 'walletAddress'> {
+  frozen: boolean;
+}
+
+export interface DistributeCashLinkInput extends Omit<CashLinkInput, 'walletAddress'> {
+  wallets: string[];
+}
+
 export interface SettleAndTransferInput {
   walletAddress: string;
   transferTokenMintAddress: string;
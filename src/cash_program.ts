@@ -2,11 +2,15 @@ import { PublicKey } from '@solana/web3.js';
 import { Program } from '@metaplex-foundation/mpl-core';
 import { CashLink } from './accounts';
 import bs58 from 'bs58';
+import BN from 'bn.js';
+import { createHash } from 'crypto';
 
 export class CashProgram extends Program {
   static readonly PREFIX = 'cash';
   static readonly FINGERPRINT_PREFIX = 'fingerprint';
   static readonly REDEMPTION_PREFIX = 'redeem';
+  static readonly CLAIM_PREFIX = 'claim';
+  static readonly CONFIG_PREFIX = 'config';
   static readonly PUBKEY = new PublicKey('cashQKx31fVsquVKXQ9prKqVtSYf8SqcYt9Jyvg966q');
 
   static async findCashLinkAccount(passKey: PublicKey): Promise<[PublicKey, number]> {
@@ -16,6 +20,41 @@ export class CashProgram extends Program {
     );
   }
 
+  static findConfigAccount(): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from(CashProgram.CONFIG_PREFIX)],
+      CashProgram.PUBKEY,
+    );
+  }
+
+  // public and unsalted: short codes can be brute-forced from the cash link,
+  // redemptions are only gated by the authority signature
+  static hashlock(secret: string): Buffer {
+    return createHash('sha256').update(secret).digest();
+  }
+
+  static async findHashlockCashLinkAccount(hashlock: Uint8Array): Promise<[PublicKey, number]> {
+    return CashProgram.findCashLinkAccount(new PublicKey(hashlock));
+  }
+
+  // totalClaims is the number of claims the wallet made before this one
+  static claimMessage(
+    cashLink: PublicKey,
+    wallet: PublicKey,
+    totalClaims: number,
+    expiresAt: BN,
+  ): Buffer {
+    const claims = Buffer.alloc(2);
+    claims.writeUInt16LE(totalClaims);
+    return Buffer.concat([
+      Buffer.from(CashProgram.CLAIM_PREFIX),
+      cashLink.toBuffer(),
+      wallet.toBuffer(),
+      claims,
+      expiresAt.toArrayLike(Buffer, 'le', 8),
+    ]);
+  }
+
   static async findRedemptionAccount(
     cashLink: PublicKey,
     wallet: PublicKey,
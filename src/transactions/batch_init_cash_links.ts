@@ -0,0 +1,31 @@
+import { PublicKey } from '@solana/web3.js';
+import { InitArgs, InitCashLinkArgs } from './init_cash_link';
+
+export type BatchInitArgs = {
+  args: InitArgs;
+  cashLinkBumps: number[];
+};
+
+export class BatchInitCashLinksArgs {
+  static readonly INSTRUCTION = 4;
+
+  // the shared args serialize as InitCashLinkArgs without their instruction byte
+  static serialize({ args, cashLinkBumps }: BatchInitArgs): Buffer {
+    const bumps = Buffer.alloc(4 + cashLinkBumps.length);
+    bumps.writeUInt32LE(cashLinkBumps.length, 0);
+    Buffer.from(cashLinkBumps).copy(bumps, 4);
+    return Buffer.concat([
+      Buffer.from([BatchInitCashLinksArgs.INSTRUCTION]),
+      InitCashLinkArgs.serialize(args).subarray(1),
+      bumps,
+    ]);
+  }
+}
+
+export type BatchInitCashLinksParams = Omit<InitArgs, 'cashLinkBump'> & {
+  authority: PublicKey;
+  feePayer: PublicKey;
+  owner: PublicKey;
+  mint?: PublicKey | null;
+  cashLinks: { cashLink: PublicKey; passKey: PublicKey; cashLinkBump: number }[];
+};
@@ -3,15 +3,20 @@ import { PublicKey } from '@solana/web3.js';
 
 export type InitCancelArgs = {
   cashLinkBump: number;
+  reason?: number;
+  owner?: PublicKey;
+  bundleMints?: PublicKey[];
 };
 
 export class CancelCashLinkArgs extends Borsh.Data<InitCancelArgs> {
   static readonly SCHEMA = CancelCashLinkArgs.struct([
     ['instruction', 'u8'],
     ['cashLinkBump', 'u8'],
+    ['reason', { kind: 'option', type: 'u8' }],
   ]);
   instruction = 2;
   cashLinkBump: number;
+  reason?: number;
 }
 
 export type CancelCashLinkParams = {
@@ -22,4 +27,8 @@ export type CancelCashLinkParams = {
   vaultToken?: PublicKey | null;
   feePayer: PublicKey;
   cashLinkBump: number;
+  reason?: number;
+  owner?: PublicKey;
+  stake?: PublicKey;
+  bundleMints?: PublicKey[];
 };
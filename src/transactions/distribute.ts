@@ -0,0 +1,30 @@
+import { Borsh } from '@metaplex-foundation/mpl-core';
+import { PublicKey } from '@solana/web3.js';
+
+export type DistributeArgsData = {
+  cashLinkBump: number;
+  redemptionBumps: number[];
+};
+
+export class DistributeArgs extends Borsh.Data<DistributeArgsData> {
+  static readonly SCHEMA = DistributeArgs.struct([
+    ['instruction', 'u8'],
+    ['cashLinkBump', 'u8'],
+    ['redemptionBumps', ['u8']],
+  ]);
+
+  instruction = 5;
+}
+
+export type DistributeParams = {
+  authority: PublicKey;
+  cashLink: PublicKey;
+  cashLinkBump: number;
+  passKey: PublicKey;
+  feeToken: PublicKey;
+  ownerToken: PublicKey;
+  owner?: PublicKey;
+  feePayer: PublicKey;
+  mint?: PublicKey | null;
+  wallets: { wallet: PublicKey; redemption: PublicKey; redemptionBump: number }[];
+};
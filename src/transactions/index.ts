@@ -2,3 +2,9 @@ export * from './init_cash_link';
 export * from './redeem_cash_link';
 export * from './cancel_cash_link';
 export * from './close_cash_link';
+export * from './batch_init_cash_links';
+export * from './distribute';
+export * from './refund_cash_link';
+export * from './set_config';
+export * from './set_frozen';
+export * from './migrate_cash_link';
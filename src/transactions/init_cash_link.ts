@@ -1,8 +1,22 @@
-import { Borsh } from '@metaplex-foundation/mpl-core';
+import { Borsh, StringPublicKey } from '@metaplex-foundation/mpl-core';
 import { PublicKey } from '@solana/web3.js';
 import BN from 'bn.js';
 import { CashLinkDistributionType } from 'src/accounts';
 
+export type BundleAssetArgs = {
+  mint: StringPublicKey;
+  amount: BN;
+};
+
+export class BundleAssetData extends Borsh.Data<BundleAssetArgs> {
+  static readonly SCHEMA = BundleAssetData.struct([
+    ['mint', 'pubkeyAsString'],
+    ['amount', 'u64'],
+  ]);
+  mint: StringPublicKey;
+  amount: BN;
+}
+
 export type InitArgs = {
   amount: BN;
   feeBps: number;
@@ -14,21 +28,52 @@ export type InitArgs = {
   minAmount?: BN;
   fingerprintEnabled?: boolean;
   numDaysToExpire: number;
+  allowlistRoot?: Uint8Array;
+  hashlock?: Uint8Array;
+  maxClaimsPerWallet?: number;
+  cooldownSeconds?: BN;
+  walletCooldownSeconds?: BN;
+  bundle?: BundleAssetData[];
+  nft?: boolean;
+  feesInSol?: boolean;
+  relayerFee?: BN;
+  refundBounty?: BN;
+  forbidCpi?: boolean;
+  deniedPrograms?: StringPublicKey[];
+  stake?: StringPublicKey;
+  refundTo?: StringPublicKey;
 };
 
 export class InitCashLinkArgs extends Borsh.Data<InitArgs> {
-  static readonly SCHEMA = InitCashLinkArgs.struct([
-    ['instruction', 'u8'],
-    ['amount', 'u64'],
-    ['feeBps', 'u16'],
-    ['fixedFee', 'u64'],
-    ['feeToRedeem', 'u64'],
-    ['cashLinkBump', 'u8'],
-    ['distributionType', 'u8'],
-    ['maxNumRedemptions', 'u16'],
-    ['minAmount', { kind: 'option', type: 'u64' }],
-    ['fingerprintEnabled', { kind: 'option', type: 'u8' }],
-    ['numDaysToExpire', 'u8'],
+  static readonly SCHEMA = new Map([
+    ...BundleAssetData.SCHEMA,
+    ...InitCashLinkArgs.struct([
+      ['instruction', 'u8'],
+      ['amount', 'u64'],
+      ['feeBps', 'u16'],
+      ['fixedFee', 'u64'],
+      ['feeToRedeem', 'u64'],
+      ['cashLinkBump', 'u8'],
+      ['distributionType', 'u8'],
+      ['maxNumRedemptions', 'u16'],
+      ['minAmount', { kind: 'option', type: 'u64' }],
+      ['fingerprintEnabled', { kind: 'option', type: 'u8' }],
+      ['numDaysToExpire', 'u8'],
+      ['allowlistRoot', { kind: 'option', type: [32] }],
+      ['hashlock', { kind: 'option', type: [32] }],
+      ['maxClaimsPerWallet', { kind: 'option', type: 'u16' }],
+      ['cooldownSeconds', { kind: 'option', type: 'u64' }],
+      ['walletCooldownSeconds', { kind: 'option', type: 'u64' }],
+      ['bundle', { kind: 'option', type: [BundleAssetData] }],
+      ['nft', { kind: 'option', type: 'u8' }],
+      ['feesInSol', { kind: 'option', type: 'u8' }],
+      ['relayerFee', { kind: 'option', type: 'u64' }],
+      ['refundBounty', { kind: 'option', type: 'u64' }],
+      ['forbidCpi', { kind: 'option', type: 'u8' }],
+      ['deniedPrograms', { kind: 'option', type: ['pubkeyAsString'] }],
+      ['stake', { kind: 'option', type: 'pubkeyAsString' }],
+      ['refundTo', { kind: 'option', type: 'pubkeyAsString' }],
+    ]),
   ]);
 
   instruction = 0;
@@ -42,6 +87,20 @@ export class InitCashLinkArgs extends Borsh.Data<InitArgs> {
   minAmount?: BN;
   fingerprintEnabled?: boolean;
   numDaysToExpire: number;
+  allowlistRoot?: Uint8Array;
+  hashlock?: Uint8Array;
+  maxClaimsPerWallet?: number;
+  cooldownSeconds?: BN;
+  walletCooldownSeconds?: BN;
+  bundle?: BundleAssetData[];
+  nft?: boolean;
+  feesInSol?: boolean;
+  relayerFee?: BN;
+  refundBounty?: BN;
+  forbidCpi?: boolean;
+  deniedPrograms?: StringPublicKey[];
+  stake?: StringPublicKey;
+  refundTo?: StringPublicKey;
 }
 
 export type InitCashLinkParams = {
@@ -61,4 +120,18 @@ export type InitCashLinkParams = {
   minAmount?: BN;
   fingerprintEnabled?: boolean;
   numDaysToExpire: number;
+  allowlistRoot?: Uint8Array;
+  hashlock?: Uint8Array;
+  maxClaimsPerWallet?: number;
+  cooldownSeconds?: BN;
+  walletCooldownSeconds?: BN;
+  bundle?: BundleAssetData[];
+  nft?: boolean;
+  feesInSol?: boolean;
+  relayerFee?: BN;
+  refundBounty?: BN;
+  forbidCpi?: boolean;
+  deniedPrograms?: StringPublicKey[];
+  stake?: StringPublicKey;
+  refundTo?: StringPublicKey;
 };
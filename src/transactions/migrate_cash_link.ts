@@ -0,0 +1,12 @@
+import { Borsh } from '@metaplex-foundation/mpl-core';
+import { PublicKey } from '@solana/web3.js';
+export class MigrateCashLinkArgs extends Borsh.Data {
+  static readonly SCHEMA = MigrateCashLinkArgs.struct([['instruction', 'u8']]);
+
+  instruction = 9;
+}
+
+export type MigrateCashLinkParams = {
+  feePayer: PublicKey;
+  cashLink: PublicKey;
+};
@@ -1,11 +1,17 @@
-import { Borsh } from '@metaplex-foundation/mpl-core';
+import { Borsh, StringPublicKey } from '@metaplex-foundation/mpl-core';
 import { PublicKey } from '@solana/web3.js';
+import BN from 'bn.js';
 
 export type RedeemArgs = {
   redemptionBump: number;
   cashLinkBump: number;
   fingerprint?: string;
   fingerprintBump?: number;
+  allowlistProof?: Uint8Array[];
+  allowlistAmount?: BN;
+  claimExpiresAt?: BN;
+  secret?: string;
+  recipient?: StringPublicKey;
 };
 
 export class RedeemCashLinkArgs extends Borsh.Data<RedeemArgs> {
@@ -15,6 +21,11 @@ export class RedeemCashLinkArgs extends Borsh.Data<RedeemArgs> {
     ['cashLinkBump', 'u8'],
     ['fingerprint', { kind: 'option', type: 'string' }],
     ['fingerprintBump', { kind: 'option', type: 'u8' }],
+    ['allowlistProof', { kind: 'option', type: [[32]] }],
+    ['allowlistAmount', { kind: 'option', type: 'u64' }],
+    ['claimExpiresAt', { kind: 'option', type: 'u64' }],
+    ['secret', { kind: 'option', type: 'string' }],
+    ['recipient', { kind: 'option', type: 'pubkeyAsString' }],
   ]);
 
   instruction = 1;
@@ -27,6 +38,7 @@ export type RedeemCashLinkParams = {
   vaultToken?: PublicKey;
   walletToken: PublicKey;
   ownerToken: PublicKey;
+  owner?: PublicKey;
   feeToken: PublicKey;
   feePayer: PublicKey;
   redemptionBump: number;
@@ -37,4 +49,11 @@ export type RedeemCashLinkParams = {
   fingerprint?: string;
   fingerprintBump?: number;
   mint?: PublicKey;
+  allowlistProof?: Uint8Array[];
+  allowlistAmount?: BN;
+  claimExpiresAt?: BN;
+  secret?: string;
+  stake?: PublicKey;
+  bundleMints?: PublicKey[];
+  recipient?: PublicKey;
 };
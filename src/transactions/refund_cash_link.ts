@@ -0,0 +1,27 @@
+import { Borsh } from '@metaplex-foundation/mpl-core';
+import { PublicKey } from '@solana/web3.js';
+
+export type RefundArgs = {
+  cashLinkBump: number;
+};
+
+export class RefundCashLinkArgs extends Borsh.Data<RefundArgs> {
+  static readonly SCHEMA = RefundCashLinkArgs.struct([
+    ['instruction', 'u8'],
+    ['cashLinkBump', 'u8'],
+  ]);
+  instruction = 6;
+  cashLinkBump: number;
+}
+
+export type RefundCashLinkParams = {
+  cranker: PublicKey;
+  cashLink: PublicKey;
+  passKey: PublicKey;
+  owner: PublicKey;
+  ownerToken: PublicKey;
+  vaultToken?: PublicKey | null;
+  cashLinkBump: number;
+  stake?: PublicKey;
+  bundleMints?: PublicKey[];
+};
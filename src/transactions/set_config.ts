@@ -0,0 +1,29 @@
+import { Borsh, StringPublicKey } from '@metaplex-foundation/mpl-core';
+import { PublicKey } from '@solana/web3.js';
+
+export type ConfigArgs = {
+  configBump: number;
+  admin: StringPublicKey;
+  paused: boolean;
+};
+
+export class SetConfigArgs extends Borsh.Data<ConfigArgs> {
+  static readonly SCHEMA = SetConfigArgs.struct([
+    ['instruction', 'u8'],
+    ['configBump', 'u8'],
+    ['admin', 'pubkeyAsString'],
+    ['paused', 'u8'],
+  ]);
+  instruction = 7;
+  configBump: number;
+  admin: StringPublicKey;
+  paused: boolean;
+}
+
+export type SetConfigParams = {
+  signer: PublicKey;
+  feePayer: PublicKey;
+  admin: PublicKey;
+  paused: boolean;
+  programData?: PublicKey;
+};
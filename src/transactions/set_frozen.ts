@@ -0,0 +1,21 @@
+import { Borsh } from '@metaplex-foundation/mpl-core';
+import { PublicKey } from '@solana/web3.js';
+
+export type FrozenArgs = {
+  frozen: boolean;
+};
+
+export class SetFrozenArgs extends Borsh.Data<FrozenArgs> {
+  static readonly SCHEMA = SetFrozenArgs.struct([
+    ['instruction', 'u8'],
+    ['frozen', 'u8'],
+  ]);
+  instruction = 8;
+  frozen: boolean;
+}
+
+export type SetFrozenParams = {
+  authority: PublicKey;
+  cashLink: PublicKey;
+  frozen: boolean;
+};